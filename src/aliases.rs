@@ -0,0 +1,74 @@
+use std::collections::BTreeMap;
+
+use crate::models::{normalize_name, EBlock, JDay};
+
+// longest alias chain followed, so a cycle in the config cannot hang us
+const MAX_HOPS: usize = 8;
+
+// Resolves an exercise to the parent lift it is grouped under. Config entries
+// map a name or tag to its parent; without one, the first secondary hashtag
+// of the block (the `#sq` in `#safety-squat #sq`) is the parent.
+#[derive(Debug, Clone, Default)]
+pub struct Aliases {
+    parents: BTreeMap<String, String>,
+}
+
+impl Aliases {
+    pub fn new(config: &BTreeMap<String, String>) -> Self {
+        let parents = config.iter()
+            .map(|(child, parent)| (normalize_name(child), parent.trim_start_matches('#').to_string()))
+            .collect();
+        Aliases { parents }
+    }
+
+    fn follow(&self, name: &str) -> Option<String> {
+        let mut parent = self.parents.get(&normalize_name(name))?;
+        for _ in 0..MAX_HOPS {
+            match self.parents.get(&normalize_name(parent)) {
+                Some(next) if normalize_name(next) != normalize_name(parent) => parent = next,
+                _ => break,
+            }
+        }
+        Some(parent.clone())
+    }
+
+    // the name the block's exercise is grouped under
+    pub fn resolve(&self, jday: &JDay, eblock: &EBlock) -> Option<String> {
+        let ex = jday.exercise(&eblock.eid)?;
+        let base = ex.name.split(" #").next().unwrap_or("").trim();
+        if let Some(parent) = self.follow(base) {
+            return Some(parent);
+        }
+        let secondary = jday.tags(eblock).into_iter()
+            .find(|t| normalize_name(t) != normalize_name(base));
+        Some(match secondary {
+            Some(tag) => self.follow(&tag).unwrap_or(tag),
+            None => base.to_string(),
+        })
+    }
+}
+
+// the block is the queried exercise or one of its tags, or with `aliases` a
+// variant grouped under it
+pub fn block_matches(jday: &JDay, eblock: &EBlock, query: &str, aliases: Option<&Aliases>) -> bool {
+    jday.eblock_matches(eblock, query)
+        || aliases.and_then(|a| a.resolve(jday, eblock))
+            .is_some_and(|parent| parent.eq_ignore_ascii_case(query.trim_start_matches('#')))
+}
+
+// The workouts with every exercise renamed to its parent lift, so analyses
+// keyed by name add variants up. Blocks keep their ids.
+pub fn group_days(days: &[(String, JDay)], aliases: &Aliases) -> Vec<(String, JDay)> {
+    days.iter()
+        .map(|(date, jday)| {
+            let mut grouped = jday.clone();
+            for wrapper in &mut grouped.exercises {
+                let block = jday.eblocks.iter().find(|b| b.eid == wrapper.exercise.id);
+                if let Some(parent) = block.and_then(|b| aliases.resolve(jday, b)) {
+                    wrapper.exercise.name = parent;
+                }
+            }
+            (date.clone(), grouped)
+        })
+        .collect()
+}
@@ -1,12 +1,11 @@
 use async_trait::async_trait;
 use reqwest::Client;
 use serde::de::DeserializeOwned;
-use serde_json;
 use ansi_term::Colour;
 use tokio::sync::OnceCell;
 
 use crate::models::{GraphQLRequest, GraphQLResponse, WorkoutRequest, WorkoutResponse, UserBasicInfoData, User};
-use crate::formatters::STDERR_COLOR_ENABLED;
+use crate::formatters::stderr_color_enabled;
 
 #[cfg_attr(tarpaulin, ignore)]
 #[async_trait]
@@ -40,7 +39,7 @@ impl ApiClient for ReqwestClient {
         if self.verbose {
             let mut output = format!("Query:\n{}", request.query);
             output += &format!("\nVariables: {}", serde_json::to_string_pretty(&request.variables).unwrap_or("Failed".to_string()));
-            let colored = if *STDERR_COLOR_ENABLED {
+            let colored = if stderr_color_enabled() {
                 Colour::Blue.paint(output).to_string()
             } else {
                 output
@@ -56,13 +55,13 @@ impl ApiClient for ReqwestClient {
         let text = response.text().await?;
         if self.verbose {
             let colored = if status.is_success() {
-                if *STDERR_COLOR_ENABLED {
+                if stderr_color_enabled() {
                     Colour::Green.paint(&text).to_string()
                 } else {
                     text.clone()
                 }
             } else {
-                if *STDERR_COLOR_ENABLED {
+                if stderr_color_enabled() {
                     Colour::Red.paint(&text).to_string()
                 } else {
                     text.clone()
@@ -80,7 +79,7 @@ impl ApiClient for ReqwestClient {
             if let Some(vars) = &variables {
                 output += &format!("\nVariables: {}", serde_json::to_string_pretty(vars).unwrap_or("Failed".to_string()));
             }
-            let colored = if *STDERR_COLOR_ENABLED {
+            let colored = if stderr_color_enabled() {
                 Colour::Blue.paint(output).to_string()
             } else {
                 output
@@ -102,13 +101,13 @@ impl ApiClient for ReqwestClient {
         let text = response.text().await?;
         if self.verbose {
             let colored = if status.is_success() {
-                if *STDERR_COLOR_ENABLED {
+                if stderr_color_enabled() {
                     Colour::Green.paint(&text).to_string()
                 } else {
                     text.clone()
                 }
             } else {
-                if *STDERR_COLOR_ENABLED {
+                if stderr_color_enabled() {
                     Colour::Red.paint(&text).to_string()
                 } else {
                     text.clone()
@@ -161,7 +160,6 @@ pub async fn graphql_request<T: DeserializeOwned + 'static, C: ApiClient>(client
 }
 
 #[cfg_attr(tarpaulin, ignore)]
-#[allow(dead_code)]
 pub async fn workout_request(client: &Client, token: &str, request: &WorkoutRequest) -> Result<WorkoutResponse, Box<dyn std::error::Error>> {
     let response = client
         .post("https://weightxreps.net/api/graphql")
@@ -21,15 +21,14 @@ struct CachedToken {
 
 pub async fn login<C: crate::api::ApiClient>(client: &C, credentials_path: &str, token_path: &str) -> Result<String, String> {
     // Check if token file exists and is valid
-    if let Ok(contents) = fs::read_to_string(token_path) {
-        if let Ok(cached) = serde_json::from_str::<CachedToken>(&contents) {
-            let now = std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_secs();
-            if cached.exp > now {
-                return Ok(cached.token);
-            }
+    if let Ok(contents) = fs::read_to_string(token_path)
+        && let Ok(cached) = serde_json::from_str::<CachedToken>(&contents) {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        if cached.exp > now {
+            return Ok(cached.token);
         }
     }
 
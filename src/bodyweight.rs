@@ -0,0 +1,88 @@
+use chrono::NaiveDate;
+
+use crate::charts;
+use crate::formatters;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BwEntry {
+    pub date: NaiveDate,
+    pub bw: f32,
+    pub avg7: f32,
+    pub avg28: f32,
+}
+
+// mean of the weigh-ins in the `days` calendar days ending at `end`
+fn window_mean(weights: &[(NaiveDate, f32)], end: NaiveDate, days: i64) -> f32 {
+    let start = end - chrono::Duration::days(days - 1);
+    let window: Vec<f32> = weights.iter()
+        .filter(|(d, _)| *d >= start && *d <= end)
+        .map(|(_, w)| *w)
+        .collect();
+    window.iter().sum::<f32>() / window.len().max(1) as f32
+}
+
+// `weights` are (date, bw) pairs, oldest first
+pub fn moving_averages(weights: &[(NaiveDate, f32)]) -> Vec<BwEntry> {
+    weights.iter()
+        .map(|&(date, bw)| BwEntry {
+            date,
+            bw,
+            avg7: window_mean(weights, date, 7),
+            avg28: window_mean(weights, date, 28),
+        })
+        .collect()
+}
+
+// least-squares slope, in weight per week
+pub fn weekly_rate(weights: &[(NaiveDate, f32)]) -> Option<f32> {
+    let first = weights.first()?.0;
+    let points: Vec<(f32, f32)> = weights.iter()
+        .map(|(d, w)| ((*d - first).num_days() as f32, *w))
+        .collect();
+    let n = points.len() as f32;
+    let mean_x = points.iter().map(|p| p.0).sum::<f32>() / n;
+    let mean_y = points.iter().map(|p| p.1).sum::<f32>() / n;
+    let sxx: f32 = points.iter().map(|p| (p.0 - mean_x).powi(2)).sum();
+    if sxx == 0.0 {
+        return None;
+    }
+    let sxy: f32 = points.iter().map(|p| (p.0 - mean_x) * (p.1 - mean_y)).sum();
+    Some(sxy / sxx * 7.0)
+}
+
+fn rate_line(label: &str, rate: Option<f32>) -> Option<String> {
+    let rate = (rate? * 10.0).round() / 10.0;
+    Some(format!("{}  {}/wk", label, formatters::color_delta(&formatters::format_delta(rate), rate)))
+}
+
+// weights must already be in the unit to display
+pub fn render_bodyweight(weights: &[(NaiveDate, f32)]) -> String {
+    let entries = moving_averages(weights);
+    let rows: Vec<Vec<String>> = entries.iter()
+        .map(|e| vec![
+            formatters::color_date(&e.date.to_string()),
+            formatters::color_bw(&format!("{:.1}", e.bw)),
+            format!("{:.1}", e.avg7),
+            format!("{:.1}", e.avg28),
+        ])
+        .collect();
+    let mut out = vec![formatters::render_table(&["date", "bw", "7d avg", "28d avg"], &rows)];
+    if entries.len() > 1 {
+        let avg7: Vec<f32> = entries.iter().map(|e| e.avg7).collect();
+        out.push(String::new());
+        out.push(charts::line_chart(&avg7, 8, |v| format!("{:.1}", v)));
+        out.push(String::new());
+        if let Some(line) = rate_line("rate over range  ", weekly_rate(weights)) {
+            out.push(line);
+        }
+        let last = weights[weights.len() - 1].0;
+        let recent: Vec<(NaiveDate, f32)> = weights.iter()
+            .filter(|(d, _)| (last - *d).num_days() < 28)
+            .copied()
+            .collect();
+        if let Some(line) = rate_line("rate last 4 weeks", weekly_rate(&recent)) {
+            out.push(line);
+        }
+    }
+    out.join("\n")
+}
@@ -0,0 +1,92 @@
+use std::collections::BTreeMap;
+use chrono::{Datelike, NaiveDate};
+
+use crate::formatters;
+use crate::metrics;
+use crate::models::JDay;
+
+// what the shade of each day stands for
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Metric {
+    // trained or not
+    #[default]
+    Presence,
+    // w x r x s over the whole workout
+    Tonnage,
+    // working-set count
+    Sets,
+}
+
+const SHADES: [char; 5] = ['·', '░', '▒', '▓', '█'];
+const MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+const WEEKDAYS: [&str; 7] = ["Mon", "", "Wed", "", "Fri", "", "Sun"];
+
+pub fn day_value(jday: &JDay, metric: Metric) -> f32 {
+    match metric {
+        Metric::Presence => 1.0,
+        Metric::Tonnage => jday.eblocks.iter().map(|b| metrics::total_volume(&b.sets)).sum(),
+        Metric::Sets => jday.eblocks.iter().map(|b| metrics::working_sets(&b.sets)).sum::<u32>() as f32,
+    }
+}
+
+// 0 for rest days, otherwise 1-4 by quarters of the busiest day
+pub fn level(value: f32, max: f32) -> usize {
+    if value <= 0.0 || max <= 0.0 {
+        0
+    } else {
+        ((value / max * 4.0).ceil() as usize).clamp(1, 4)
+    }
+}
+
+// One column per week (Monday first), one row per weekday, with month names
+// above the week they start in. `values` holds the metric per training day.
+pub fn render_calendar(year: i32, values: &BTreeMap<NaiveDate, f32>) -> String {
+    let Some(jan1) = NaiveDate::from_ymd_opt(year, 1, 1) else {
+        return String::new();
+    };
+    let start = jan1 - chrono::Duration::days(jan1.weekday().num_days_from_monday() as i64);
+    let dec31 = NaiveDate::from_ymd_opt(year, 12, 31).unwrap();
+    let weeks = ((dec31 - start).num_days() / 7 + 1) as usize;
+    let max = values.values().copied().fold(0.0, f32::max);
+
+    let mut months = vec![' '; weeks * 2];
+    let mut next_free = 0;
+    for week in 0..weeks {
+        let first = (0..7)
+            .map(|d| start + chrono::Duration::days((week * 7 + d) as i64))
+            .find(|d| d.year() == year);
+        if let Some(day) = first
+            && (day.day() <= 7 || week == 0)
+            && week * 2 >= next_free {
+            for (i, c) in MONTHS[day.month0() as usize].chars().enumerate() {
+                if let Some(slot) = months.get_mut(week * 2 + i) {
+                    *slot = c;
+                }
+            }
+            next_free = week * 2 + 4;
+        }
+    }
+    let mut out = vec![format!("    {}", months.iter().collect::<String>()).trim_end().to_string()];
+
+    for (weekday, label) in WEEKDAYS.iter().enumerate() {
+        let mut line = format!("{:<4}", label);
+        for week in 0..weeks {
+            let day = start + chrono::Duration::days((week * 7 + weekday) as i64);
+            if day.year() != year {
+                line.push_str("  ");
+                continue;
+            }
+            let level = level(values.get(&day).copied().unwrap_or(0.0), max);
+            line.push_str(&formatters::color_heat(&SHADES[level].to_string(), level));
+            line.push(' ');
+        }
+        out.push(line.trim_end().to_string());
+    }
+
+    let legend: String = SHADES.iter().enumerate()
+        .map(|(i, c)| formatters::color_heat(&c.to_string(), i))
+        .collect();
+    out.push(String::new());
+    out.push(format!("{} sessions in {}    less {} more", values.len(), year, legend));
+    out.join("\n")
+}
@@ -0,0 +1,84 @@
+// widest chart drawn; longer series are squeezed by keeping each bucket's peak
+const MAX_CHART_WIDTH: usize = 100;
+
+const SPARKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+fn bounds(values: &[f32]) -> (f32, f32) {
+    let min = values.iter().copied().fold(f32::INFINITY, f32::min);
+    let max = values.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    (min, max)
+}
+
+// one character per value, scaled between the smallest and largest value
+pub fn sparkline(values: &[f32]) -> String {
+    if values.is_empty() {
+        return String::new();
+    }
+    let (min, max) = bounds(values);
+    values.iter()
+        .map(|&v| {
+            let level = if max > min { (v - min) / (max - min) * 7.0 } else { 3.0 };
+            SPARKS[level.round() as usize]
+        })
+        .collect()
+}
+
+pub fn downsample(values: &[f32], width: usize) -> Vec<f32> {
+    if values.len() <= width || width == 0 {
+        return values.to_vec();
+    }
+    (0..width)
+        .map(|i| {
+            let start = i * values.len() / width;
+            let end = ((i + 1) * values.len() / width).max(start + 1);
+            values[start..end].iter().copied().fold(f32::NEG_INFINITY, f32::max)
+        })
+        .collect()
+}
+
+// A dot per value on a grid `height` rows tall, with the top and bottom of
+// the y axis labelled by `label`. Consecutive dots are joined vertically so
+// the line stays readable when it jumps.
+pub fn line_chart(values: &[f32], height: usize, label: impl Fn(f32) -> String) -> String {
+    if values.is_empty() || height < 2 {
+        return String::new();
+    }
+    let values = &downsample(values, MAX_CHART_WIDTH);
+    let (min, max) = bounds(values);
+    let row_of = |v: f32| -> usize {
+        if max > min {
+            ((v - min) / (max - min) * (height - 1) as f32).round() as usize
+        } else {
+            (height - 1) / 2
+        }
+    };
+    let rows: Vec<usize> = values.iter().map(|&v| row_of(v)).collect();
+    let mut grid = vec![vec![' '; values.len()]; height];
+    for (x, &row) in rows.iter().enumerate() {
+        if x > 0 {
+            let (low, high) = (rows[x - 1].min(row), rows[x - 1].max(row));
+            if high > low + 1 {
+                for line in &mut grid[low + 1..high] {
+                    line[x] = '│';
+                }
+            }
+        }
+        grid[row][x] = '●';
+    }
+    let top = label(max);
+    let bottom = label(min);
+    let width = top.chars().count().max(bottom.chars().count());
+    let mut lines = Vec::new();
+    for (i, row) in grid.iter().enumerate().rev() {
+        let axis = if i == height - 1 {
+            format!("{:>width$}", top)
+        } else if i == 0 {
+            format!("{:>width$}", bottom)
+        } else {
+            " ".repeat(width)
+        };
+        lines.push(format!("{} ┤{}", axis, row.iter().collect::<String>()).trim_end().to_string());
+    }
+    lines.push(format!("{} └{}", " ".repeat(width), "─".repeat(values.len())));
+    lines.join("\n")
+}
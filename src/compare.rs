@@ -0,0 +1,126 @@
+use std::collections::BTreeMap;
+
+use crate::formatters;
+use crate::metrics;
+use crate::models::{JDay, Set};
+use crate::stats;
+
+// one exercise over one period; weights in kg
+#[derive(Debug, Default, Clone)]
+pub struct ExercisePeriod {
+    pub sessions: u32,
+    pub tonnage: f32,
+    pub top: Option<Set>,
+    pub best_e1rm: f32,
+    rpe_sum: f32,
+    rpe_sets: u32,
+}
+
+impl ExercisePeriod {
+    pub fn avg_rpe(&self) -> Option<f32> {
+        (self.rpe_sets > 0).then(|| self.rpe_sum / self.rpe_sets as f32)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct PeriodSummary {
+    pub sessions: u32,
+    pub tonnage: f32,
+    // last bodyweight logged, in kg
+    pub bw: Option<f32>,
+    pub exercises: BTreeMap<String, ExercisePeriod>,
+}
+
+// `days` are (YYYY-MM-DD, workout) pairs, oldest first
+pub fn summarize_period(days: &[(String, JDay)]) -> PeriodSummary {
+    let mut summary = PeriodSummary { sessions: days.len() as u32, ..Default::default() };
+    for (_, jday) in days {
+        for (name, totals) in stats::day_totals(jday) {
+            let entry = summary.exercises.entry(name).or_default();
+            entry.sessions += 1;
+            entry.tonnage += totals.tonnage;
+            entry.best_e1rm = entry.best_e1rm.max(totals.top_e1rm);
+            summary.tonnage += totals.tonnage;
+        }
+        for eblock in &jday.eblocks {
+            let Some(entry) = jday.exercise(&eblock.eid).and_then(|ex| summary.exercises.get_mut(&ex.name)) else {
+                continue;
+            };
+            let weighted = eblock.sets.iter().filter(|s| s.is_weight_reps());
+            entry.top = metrics::top_set(weighted.chain(entry.top.as_ref())).cloned();
+            for set in eblock.sets.iter().filter(|s| s.rpe.unwrap_or(0.0) > 0.0) {
+                let n = set.s.unwrap_or(1).max(1);
+                entry.rpe_sum += set.rpe.unwrap_or(0.0) * n as f32;
+                entry.rpe_sets += n;
+            }
+        }
+    }
+    summary.bw = days.iter().filter_map(|(_, j)| j.bw).rfind(|&w| w > 0.0);
+    summary
+}
+
+// "12000 → 13500 (+12.5%)"; `higher_is_better` picks the delta color
+fn change(a: Option<f32>, b: Option<f32>, fmt: impl Fn(f32) -> String, higher_is_better: bool) -> String {
+    match (a, b) {
+        (Some(a), Some(b)) => {
+            let delta = b - a;
+            let shown = if a != 0.0 {
+                format!("{}%", formatters::format_delta((delta / a * 1000.0).round() / 10.0))
+            } else {
+                formatters::format_delta((delta * 10.0).round() / 10.0)
+            };
+            let color = if higher_is_better { delta } else { -delta };
+            format!("{} → {} ({})", fmt(a), fmt(b), formatters::color_delta(&shown, color))
+        }
+        (Some(a), None) => format!("{} → -", fmt(a)),
+        (None, Some(b)) => format!("- → {}", fmt(b)),
+        (None, None) => String::new(),
+    }
+}
+
+fn top_cell(e: Option<&ExercisePeriod>) -> String {
+    e.and_then(|e| e.top.as_ref())
+        .map(|s| format!("{}x{}", formatters::format_load(s), s.r.unwrap_or(0)))
+        .unwrap_or("-".to_string())
+}
+
+// `user_bw` converts a bodyweight in kg to the unit shown
+pub fn render_compare(label_a: &str, label_b: &str, a: &PeriodSummary, b: &PeriodSummary, lb: bool, user_bw: impl Fn(f32) -> f32) -> String {
+    let weight = |v: f32| formatters::format_weight(v, lb);
+    let count = |v: f32| formatters::format_number(v);
+    let rpe = |v: f32| format!("{:.1}", v);
+    let mut out = vec![format!("{} → {}", formatters::color_date(label_a), formatters::color_date(label_b))];
+    out.push(String::new());
+    out.push(format!("sessions    {}", change(Some(a.sessions as f32), Some(b.sessions as f32), count, true)));
+    out.push(format!("tonnage     {}", change(Some(a.tonnage), Some(b.tonnage), weight, true)));
+    // bodyweight at the end of each period
+    if let (Some(bw_a), Some(bw_b)) = (a.bw, b.bw) {
+        let (bw_a, bw_b) = (user_bw(bw_a), user_bw(bw_b));
+        let delta = ((bw_b - bw_a) * 10.0).round() / 10.0;
+        let shown = formatters::color_delta(&formatters::format_delta(delta), delta);
+        out.push(format!("bodyweight  {:.1} → {:.1} ({})", bw_a, bw_b, shown));
+    }
+
+    let mut names: Vec<&String> = a.exercises.keys().collect();
+    names.extend(b.exercises.keys().filter(|n| !a.exercises.contains_key(*n)));
+    names.sort();
+    let rows: Vec<Vec<String>> = names.into_iter()
+        .map(|name| {
+            let (ea, eb) = (a.exercises.get(name), b.exercises.get(name));
+            let e1rm = |e: Option<&ExercisePeriod>| e.map(|e| e.best_e1rm).filter(|&v| v > 0.0);
+            vec![
+                formatters::color_exercise(name),
+                change(ea.map(|e| e.sessions as f32), eb.map(|e| e.sessions as f32), count, true),
+                change(ea.map(|e| e.tonnage), eb.map(|e| e.tonnage), weight, true),
+                format!("{} → {}", top_cell(ea), top_cell(eb)),
+                change(e1rm(ea), e1rm(eb), weight, true),
+                change(ea.and_then(|e| e.avg_rpe()), eb.and_then(|e| e.avg_rpe()), rpe, false),
+            ]
+        })
+        .collect();
+    if !rows.is_empty() {
+        out.push(String::new());
+        out.push(formatters::render_table(&["exercise", "sessions", "tonnage", "top set", "e1RM", "avg RPE"], &rows));
+    }
+    out.join("\n")
+}
@@ -0,0 +1,49 @@
+use std::collections::BTreeMap;
+use std::fs;
+use serde::Deserialize;
+
+use crate::models::normalize_name;
+use crate::muscles::Mapping;
+use crate::parser::Unit;
+
+// Settings read from ~/.config/wxrust/config.json (or --config). Every field
+// is optional so a missing or partial file just means defaults.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    // sessions per week to measure adherence against
+    pub target_sessions_per_week: Option<f32>,
+    // replacement rows for the RPE chart: RPE -> % of 1RM for 1, 2, 3... reps
+    pub rpe_table: BTreeMap<String, Vec<f32>>,
+    // exercise name, tag or name word -> movement pattern and muscles
+    pub muscles: BTreeMap<String, Mapping>,
+    // exercise name or tag -> the parent lift it is grouped under
+    pub aliases: BTreeMap<String, String>,
+    // exercise name or tag -> training max, in the unit the user logs in
+    pub training_max: BTreeMap<String, f32>,
+    // unit of plain-text logs (parse, fmt and --source dir:)
+    pub unit: Option<Unit>,
+}
+
+impl Config {
+    pub fn training_max(&self, exercise: &str) -> Option<f32> {
+        let key = normalize_name(exercise);
+        self.training_max.iter().find(|(k, _)| normalize_name(k) == key).map(|(_, v)| *v)
+    }
+}
+
+pub fn default_path(home: &str) -> String {
+    format!("{}/.config/wxrust/config.json", home)
+}
+
+// The file given with --config must be there; without one, a missing default
+// file under `home` just means defaults. A broken file is always an error.
+pub fn load(path: Option<&str>, home: &str) -> Result<Config, String> {
+    let default = default_path(home);
+    let file = path.unwrap_or(&default);
+    match fs::read_to_string(file) {
+        Ok(contents) => serde_json::from_str(&contents).map_err(|e| format!("Invalid config file {}: {}", file, e)),
+        Err(e) if path.is_none() && e.kind() == std::io::ErrorKind::NotFound => Ok(Config::default()),
+        Err(e) => Err(format!("Cannot read config file {}: {}", file, e)),
+    }
+}
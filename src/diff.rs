@@ -0,0 +1,172 @@
+use crate::formatters::{self, color_delta, format_delta, pad_right};
+use crate::metrics;
+use crate::models::{normalize_name, Exercise, JDay, Set};
+
+// One exercise lined up across two workouts. Sets are expanded so that
+// "100 x 5 x 3" and "100 x 5, 5, 5" compare set by set.
+pub struct ExerciseDiff {
+    pub exercise: Exercise,
+    pub a: Vec<Set>,
+    pub b: Vec<Set>,
+}
+
+impl ExerciseDiff {
+    pub fn added(&self) -> bool {
+        self.a.is_empty()
+    }
+
+    pub fn removed(&self) -> bool {
+        self.b.is_empty()
+    }
+}
+
+// every set of an exercise in the workout, in order, one entry per set
+fn exercise_sets(jday: &JDay, ex: &Exercise) -> Vec<Set> {
+    let mut sets = Vec::new();
+    for eblock in jday.eblocks.iter().filter(|b| b.eid == ex.id) {
+        for set in &eblock.sets {
+            for _ in 0..set.s.unwrap_or(1).max(1) {
+                sets.push(Set { s: Some(1), ..set.clone() });
+            }
+        }
+    }
+    sets
+}
+
+// exercises in the order they were first done
+fn exercises_in_order(jday: &JDay) -> Vec<&Exercise> {
+    let mut seen: Vec<&Exercise> = Vec::new();
+    for eblock in &jday.eblocks {
+        if let Some(ex) = jday.exercise(&eblock.eid)
+            && !seen.iter().any(|e| e.id == ex.id) {
+            seen.push(ex);
+        }
+    }
+    seen
+}
+
+// the same exercise id, or failing that the same name, so that a renamed
+// exercise still pairs up and workouts from different sources do too
+fn same_exercise(x: &Exercise, y: &Exercise) -> bool {
+    x.id == y.id || normalize_name(&x.name) == normalize_name(&y.name)
+}
+
+// Line up exercises by id or name. Exercises of `a` come first, then the
+// ones only done in `b`.
+pub fn diff_workouts(a: &JDay, b: &JDay, exercise: Option<&str>) -> Vec<ExerciseDiff> {
+    let a_exercises = exercises_in_order(a);
+    let b_exercises = exercises_in_order(b);
+
+    let mut diffs = Vec::new();
+    for ex in &a_exercises {
+        let b_sets = match b_exercises.iter().find(|other| same_exercise(ex, other)) {
+            Some(other) => exercise_sets(b, other),
+            None => vec![],
+        };
+        diffs.push(ExerciseDiff { exercise: (*ex).clone(), a: exercise_sets(a, ex), b: b_sets });
+    }
+    for ex in &b_exercises {
+        if !a_exercises.iter().any(|other| same_exercise(ex, other)) {
+            diffs.push(ExerciseDiff { exercise: (*ex).clone(), a: vec![], b: exercise_sets(b, ex) });
+        }
+    }
+    if let Some(query) = exercise {
+        diffs.retain(|d| d.exercise.matches(query));
+    }
+    diffs
+}
+
+fn display_weight(set: &Set) -> f32 {
+    let w = set.w.unwrap_or(0.0);
+    if set.lb.unwrap_or(0.0) == 1.0 { w * formatters::LB_PER_KG } else { w }
+}
+
+// "w +5 r -1 @+0.5" for whatever changed between two sets
+fn set_deltas(a: &Set, b: &Set) -> String {
+    let mut parts = Vec::new();
+    let dw = (display_weight(b) - display_weight(a)).round();
+    if dw != 0.0 {
+        parts.push(color_delta(&format!("w {}", format_delta(dw)), dw));
+    }
+    let dr = b.r.unwrap_or(0) as f32 - a.r.unwrap_or(0) as f32;
+    if dr != 0.0 {
+        parts.push(color_delta(&format!("r {}", format_delta(dr)), dr));
+    }
+    let drpe = b.rpe.unwrap_or(0.0) - a.rpe.unwrap_or(0.0);
+    if drpe != 0.0 {
+        // harder is not better: flip the color
+        parts.push(color_delta(&format!("@{}", format_delta(drpe)), -drpe));
+    }
+    parts.join(" ")
+}
+
+fn render_sets(out: &mut Vec<String>, diff: &ExerciseDiff) {
+    let a_lines: Vec<(&Set, String)> = diff.a.iter().map(|s| (s, formatters::format_set(s))).collect();
+    let width = a_lines.iter().map(|(_, l)| formatters::visible_width(l)).max().unwrap_or(0);
+    for i in 0..diff.a.len().max(diff.b.len()) {
+        match (a_lines.get(i), diff.b.get(i)) {
+            (Some((a, a_line)), Some(b)) => {
+                let deltas = set_deltas(a, b);
+                let line = format!("  {} → {}", pad_right(a_line, width), formatters::format_set(b));
+                out.push(if deltas.is_empty() { line } else { format!("{}  {}", line, deltas) });
+            }
+            (Some((_, a_line)), None) => {
+                out.push(format!("- {}", color_delta(a_line, -1.0)));
+            }
+            (None, Some(b)) => {
+                out.push(format!("+ {}", color_delta(&formatters::format_set(b), 1.0)));
+            }
+            (None, None) => {}
+        }
+    }
+}
+
+// "vol 1500 → 1575 (+75)  top 100x5 → 105x5 (+5)"
+fn render_summary(diff: &ExerciseDiff) -> String {
+    let lb = diff.a.iter().chain(&diff.b).any(|s| s.lb.unwrap_or(0.0) == 1.0);
+    let vol_a = metrics::total_volume(&diff.a);
+    let vol_b = metrics::total_volume(&diff.b);
+    let scale = if lb { formatters::LB_PER_KG } else { 1.0 };
+    let dv = ((vol_b - vol_a) * scale).round();
+    let mut line = format!(
+        "vol {} → {} ({})",
+        formatters::format_weight(vol_a, lb),
+        formatters::format_weight(vol_b, lb),
+        color_delta(&format_delta(dv), dv)
+    );
+    if let (Some(ta), Some(tb)) = (metrics::top_set(&diff.a), metrics::top_set(&diff.b)) {
+        let dw = (display_weight(tb) - display_weight(ta)).round();
+        line += &format!(
+            "  top {}x{} → {}x{} ({})",
+            formatters::format_load(ta), ta.r.unwrap_or(0),
+            formatters::format_load(tb), tb.r.unwrap_or(0),
+            color_delta(&format_delta(dw), dw)
+        );
+    }
+    line
+}
+
+pub fn render_diff(date_a: &str, date_b: &str, diffs: &[ExerciseDiff]) -> String {
+    let mut out = vec![format!("{} → {}", formatters::color_date(date_a), formatters::color_date(date_b))];
+    for diff in diffs {
+        let name = format!("#{}", formatters::color_exercise(&diff.exercise.name));
+        if diff.added() {
+            out.push(format!("+ {}", name));
+        } else if diff.removed() {
+            out.push(format!("- {}", name));
+        } else {
+            out.push(name);
+        }
+        render_sets(&mut out, diff);
+    }
+    let both: Vec<&ExerciseDiff> = diffs.iter().filter(|d| !d.added() && !d.removed()).collect();
+    if !both.is_empty() {
+        out.push(String::new());
+        let width = both.iter().map(|d| d.exercise.name.chars().count() + 1).max().unwrap_or(0);
+        for diff in both {
+            let name = format!("#{}", formatters::color_exercise(&diff.exercise.name));
+            out.push(format!("{}  {}", pad_right(&name, width), render_summary(diff)));
+        }
+    }
+    out.join("\n")
+}
@@ -1,34 +1,72 @@
 use std::collections::HashMap;
+use std::sync::OnceLock;
 use lazy_static::lazy_static;
 use ansi_term::Colour;
-use atty;
+use regex::Regex;
 
+use crate::metrics::{self, BlockKind};
 use crate::models::{JDay, Set, Exercise, EBlock, User};
 
 lazy_static! {
-    static ref COLOR_ENABLED: bool = {
-        let color_arg = std::env::var("WXRUST_COLOR").unwrap_or("auto".to_string());
-        match color_arg.as_str() {
-            "always" => true,
-            "never" => false,
-            "auto" => atty::is(atty::Stream::Stdout),
-            _ => atty::is(atty::Stream::Stdout),
-        }
-    };
+    static ref ANSI_ESCAPE: Regex = Regex::new("\x1b\\[[0-9;]*m").unwrap();
+}
 
-    pub static ref STDERR_COLOR_ENABLED: bool = {
-        let color_arg = std::env::var("WXRUST_COLOR").unwrap_or("auto".to_string());
-        match color_arg.as_str() {
-            "always" => true,
-            "never" => false,
-            "auto" => atty::is(atty::Stream::Stderr),
-            _ => atty::is(atty::Stream::Stderr),
-        }
-    };
+// whether stdout and stderr get colors
+static COLOR: OnceLock<(bool, bool)> = OnceLock::new();
+
+// "always", "never" or "auto" (when writing to a terminal). Only the first
+// call counts; output rendered before any call is colored as with "auto".
+pub fn set_color(choice: &str) {
+    let _ = COLOR.set(color_choice(choice));
+}
+
+fn color_choice(choice: &str) -> (bool, bool) {
+    match choice {
+        "always" => (true, true),
+        "never" => (false, false),
+        _ => (atty::is(atty::Stream::Stdout), atty::is(atty::Stream::Stderr)),
+    }
+}
+
+fn color_enabled() -> bool {
+    COLOR.get_or_init(|| color_choice("auto")).0
+}
+
+pub fn stderr_color_enabled() -> bool {
+    COLOR.get_or_init(|| color_choice("auto")).1
+}
+
+// what `--summary` reports for each exercise
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SummaryMode {
+    // heaviest weight x reps
+    #[default]
+    Top,
+    // best estimated 1RM
+    E1rm,
+    // tonnage, w x r x s
+    Volume,
+    // total reps
+    Reps,
+    // working-set count
+    Sets,
+    All,
+}
+
+// knobs for rendering workouts, threaded through the *_with variants
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FormatOptions {
+    // mark PR sets and append e1RM and intensity to each set
+    pub annotate: bool,
+    // write weights as in a log file in this unit (true for lb): exact, with
+    // a suffix on those logged in the other unit
+    pub log_unit: Option<bool>,
+    // one set per line instead of grouping them
+    pub no_compress: bool,
 }
 
 pub fn color_date(s: &str) -> String {
-    if *COLOR_ENABLED {
+    if color_enabled() {
         Colour::RGB(157, 78, 221).paint(s).to_string()
     } else {
         s.to_string()
@@ -36,15 +74,15 @@ pub fn color_date(s: &str) -> String {
 }
 
 pub fn color_bw(s: &str) -> String {
-    if *COLOR_ENABLED {
+    if color_enabled() {
         Colour::RGB(58, 134, 255).paint(s).to_string()
     } else {
         s.to_string()
     }
 }
 
-fn color_exercise(s: &str) -> String {
-    if *COLOR_ENABLED {
+pub fn color_exercise(s: &str) -> String {
+    if color_enabled() {
         Colour::RGB(0, 150, 255).paint(s).to_string()
     } else {
         s.to_string()
@@ -52,7 +90,7 @@ fn color_exercise(s: &str) -> String {
 }
 
 fn color_weight(s: &str) -> String {
-    if *COLOR_ENABLED {
+    if color_enabled() {
         Colour::RGB(255, 121, 0).paint(s).to_string()
     } else {
         s.to_string()
@@ -60,7 +98,7 @@ fn color_weight(s: &str) -> String {
 }
 
 fn color_reps(s: &str) -> String {
-    if *COLOR_ENABLED {
+    if color_enabled() {
         Colour::RGB(0, 187, 249).paint(s).to_string()
     } else {
         s.to_string()
@@ -68,116 +106,519 @@ fn color_reps(s: &str) -> String {
 }
 
 fn color_sets(s: &str) -> String {
-    if *COLOR_ENABLED {
+    if color_enabled() {
         Colour::RGB(241, 91, 181).paint(s).to_string()
     } else {
         s.to_string()
     }
 }
 
+fn color_pr(s: &str) -> String {
+    if color_enabled() {
+        Colour::RGB(255, 215, 0).bold().paint(s).to_string()
+    } else {
+        s.to_string()
+    }
+}
+
+fn color_annotation(s: &str) -> String {
+    if color_enabled() {
+        Colour::RGB(140, 140, 140).paint(s).to_string()
+    } else {
+        s.to_string()
+    }
+}
+
+// green for gains, red for losses
+pub fn color_delta(s: &str, delta: f32) -> String {
+    if !color_enabled() || delta == 0.0 {
+        s.to_string()
+    } else if delta > 0.0 {
+        Colour::Green.paint(s).to_string()
+    } else {
+        Colour::Red.paint(s).to_string()
+    }
+}
 
+// search hits
+pub fn color_match(s: &str) -> String {
+    if color_enabled() {
+        Colour::Red.bold().paint(s).to_string()
+    } else {
+        s.to_string()
+    }
+}
+
+// heatmap shades from empty (0) to busiest (4)
+pub fn color_heat(s: &str, level: usize) -> String {
+    if !color_enabled() || level == 0 {
+        return s.to_string();
+    }
+    let (r, g, b) = [(14, 68, 41), (0, 109, 50), (38, 166, 65), (57, 211, 83)][level.min(4) - 1];
+    Colour::RGB(r, g, b).paint(s).to_string()
+}
+
+// "1 day", "3 days"
+pub fn plural(n: u32, unit: &str) -> String {
+    if n == 1 { format!("{} {}", n, unit) } else { format!("{} {}s", n, unit) }
+}
+
+// "+5", "-2.5", "0"
+pub fn format_delta(delta: f32) -> String {
+    if delta > 0.0 {
+        format!("+{}", format_number(delta))
+    } else {
+        format_number(delta)
+    }
+}
+
+// printed width, ignoring ANSI color codes
+pub fn visible_width(s: &str) -> usize {
+    ANSI_ESCAPE.replace_all(s, "").chars().count()
+}
+
+pub fn pad_right(s: &str, width: usize) -> String {
+    let pad = width.saturating_sub(visible_width(s));
+    format!("{}{}", s, " ".repeat(pad))
+}
+
+fn is_numeric_cell(s: &str) -> bool {
+    let plain = ANSI_ESCAPE.replace_all(s, "");
+    let plain = plain.trim_start_matches('+').trim_end_matches('%');
+    plain.is_empty() || plain.parse::<f64>().is_ok()
+}
+
+// aligned columns under a header; numeric columns are right-aligned
+pub fn render_table(headers: &[&str], rows: &[Vec<String>]) -> String {
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.chars().count()).collect();
+    for row in rows {
+        for (i, cell) in row.iter().enumerate() {
+            widths[i] = widths[i].max(visible_width(cell));
+        }
+    }
+    let numeric: Vec<bool> = (0..headers.len())
+        .map(|i| !rows.is_empty() && rows.iter().all(|r| r.get(i).is_none_or(|c| is_numeric_cell(c))))
+        .collect();
+    let render_row = |cells: Vec<String>| -> String {
+        cells.iter().enumerate()
+            .map(|(i, cell)| {
+                let pad = " ".repeat(widths[i].saturating_sub(visible_width(cell)));
+                if numeric[i] { format!("{}{}", pad, cell) } else { format!("{}{}", cell, pad) }
+            })
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+    let mut lines = vec![render_row(headers.iter().map(|h| h.to_string()).collect())];
+    lines.push(widths.iter().map(|w| "-".repeat(*w)).collect::<Vec<_>>().join("  "));
+    for row in rows {
+        lines.push(render_row(row.clone()));
+    }
+    lines.join("\n")
+}
+
+// the same table as GitHub-flavored Markdown; cells are used as given
+pub fn render_markdown_table(headers: &[&str], rows: &[Vec<String>]) -> String {
+    let row = |cells: &[String]| format!("| {} |", cells.join(" | "));
+    let mut lines = vec![row(&headers.iter().map(|h| h.to_string()).collect::<Vec<_>>())];
+    lines.push(format!("|{}|", vec!["---"; headers.len()].join("|")));
+    lines.extend(rows.iter().map(|r| row(r)));
+    lines.join("\n")
+}
+
+fn color_duration(s: &str) -> String {
+    if color_enabled() {
+        Colour::RGB(6, 214, 160).paint(s).to_string()
+    } else {
+        s.to_string()
+    }
+}
+
+fn color_distance(s: &str) -> String {
+    if color_enabled() {
+        Colour::RGB(255, 209, 102).paint(s).to_string()
+    } else {
+        s.to_string()
+    }
+}
+
+
+// pounds in a kilogram; weights are kept in kg and converted for display
+pub const LB_PER_KG: f32 = 2.20462;
 
 pub fn format_weight(w: f32, lb: bool) -> String {
     if lb {
-        format!("{:.0}", w * 2.20462)
+        format!("{:.0}", w * LB_PER_KG)
     } else {
         format!("{:.0}", w)
     }
 }
 
-pub fn format_set(set: &Set) -> String {
+// "BW", "BW+25" or "BW-20" for bodyweight sets, the plain weight otherwise
+pub fn format_load(set: &Set) -> String {
+    format_load_with(set, &FormatOptions::default())
+}
+
+// a weight as shown: rounded for display, exact when writing a log file
+fn weight_text(w: f32, lb: bool, opts: &FormatOptions) -> String {
+    match opts.log_unit {
+        Some(_) => format_number(if lb { w * LB_PER_KG } else { w }),
+        None => format_weight(w, lb),
+    }
+}
+
+// log files mark weights logged in the other unit
+fn unit_suffix(lb: bool, opts: &FormatOptions) -> &'static str {
+    match (opts.log_unit, lb) {
+        (Some(false), true) => "lb",
+        (Some(true), false) => "kg",
+        _ => "",
+    }
+}
+
+fn format_load_with(set: &Set, opts: &FormatOptions) -> String {
     let w = set.w.unwrap_or(0.0);
-    let r = set.r.unwrap_or(0);
-    let s = set.s.unwrap_or(1);
-    let rpe = set.rpe.unwrap_or(0.0);
     let lb = set.lb.unwrap_or(0.0) == 1.0;
-    let w_str = color_weight(&format_weight(w, lb));
-    let mut line = w_str;
+    let weight = |w: f32| weight_text(w, lb, opts) + unit_suffix(lb, opts);
+    if !set.is_bodyweight() {
+        weight(w)
+    } else if w > 0.0 {
+        format!("BW+{}", weight(w))
+    } else if w < 0.0 {
+        format!("BW-{}", weight(-w))
+    } else {
+        "BW".to_string()
+    }
+}
+
+pub fn format_number(v: f32) -> String {
+    let s = format!("{:.2}", v);
+    s.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+// t is in milliseconds; renders as m:ss or h:mm:ss, keeping tenths if present
+pub fn format_duration(t: f32) -> String {
+    let tenths = (t / 100.0).round() as u64;
+    let secs = tenths / 10;
+    let (h, m, s) = (secs / 3600, (secs / 60) % 60, secs % 60);
+    let mut out = if h > 0 {
+        format!("{}:{:02}:{:02}", h, m, s)
+    } else {
+        format!("{}:{:02}", m, s)
+    };
+    if !tenths.is_multiple_of(10) {
+        out += &format!(".{}", tenths % 10);
+    }
+    out
+}
+
+pub fn format_distance(set: &Set) -> String {
+    let d = format_number(set.d.unwrap_or(0.0));
+    match set.dunit.as_deref() {
+        Some(unit) if !unit.is_empty() => format!("{} {}", d, unit),
+        _ => d,
+    }
+}
+
+fn has_readings(set: &Set) -> bool {
+    set.speed.unwrap_or(0.0) > 0.0 || set.force.unwrap_or(0.0) > 0.0
+}
+
+// speed and force readings from velocity trackers
+fn format_readings(set: &Set) -> String {
+    let mut out = String::new();
+    if let Some(speed) = set.speed
+        && speed > 0.0 {
+        out += &format!(" {} m/s", format_number(speed));
+    }
+    if let Some(force) = set.force
+        && force > 0.0 {
+        out += &format!(" {} N", format_number(force));
+    }
+    out
+}
+
+// time and distance sets:
+//   "5 km in 25:30"      distance with time
+//   "2:00"               time only (plank)
+//   "100 x 40 m"         weight with distance (carries)
+fn format_time_distance_set(set: &Set, opts: &FormatOptions) -> String {
+    let w = set.w.unwrap_or(0.0);
+    let r = set.r.unwrap_or(0);
+    let mut parts = Vec::new();
+    if w != 0.0 || set.is_bodyweight() {
+        parts.push(color_weight(&format_load_with(set, opts)));
+    }
     if r > 0 {
-        line += " x ";
-        line += &color_reps(&r.to_string());
+        parts.push(color_reps(&r.to_string()));
     }
+    let mut line = if set.has_distance() {
+        parts.push(color_distance(&format_distance(set)));
+        let mut line = parts.join(" x ");
+        if set.has_time() {
+            line += " in ";
+            line += &color_duration(&format_duration(set.t.unwrap_or(0.0)));
+        }
+        line
+    } else {
+        parts.push(color_duration(&format_duration(set.t.unwrap_or(0.0))));
+        parts.join(" x ")
+    };
+    let s = set.s.unwrap_or(1);
     if s > 1 {
         line += " x ";
         line += &color_sets(&s.to_string());
     }
+    line
+}
+
+pub fn format_set(set: &Set) -> String {
+    format_bare_set(set, &FormatOptions::default())
+}
+
+// the set without its annotation; a PR marker follows the reps, as it does
+// in grouped sets
+fn format_bare_set(set: &Set, opts: &FormatOptions) -> String {
+    let r = set.r.unwrap_or(0);
+    let s = set.s.unwrap_or(1);
+    let rpe = set.rpe.unwrap_or(0.0);
+    let mut line = if set.has_time() || set.has_distance() {
+        format_time_distance_set(set, opts) + &pr_marker(set, opts)
+    } else {
+        let mut line = color_weight(&format_load_with(set, opts));
+        if r > 0 {
+            line += " x ";
+            line += &color_reps(&r.to_string());
+        }
+        line += &pr_marker(set, opts);
+        if s > 1 {
+            line += " x ";
+            line += &color_sets(&s.to_string());
+        }
+        line
+    };
     if rpe > 0.0 {
         line += &format!(" @{}", rpe);
     }
-    if let Some(c) = &set.c {
-        if !c.is_empty() {
-            line += &format!(" {}", c);
-        }
+    line += &format_readings(set);
+    if let Some(c) = &set.c
+        && !c.is_empty() {
+        line += &format!(" {}", c);
     }
     line
 }
 
+fn is_pr(set: &Set) -> bool {
+    set.pr.unwrap_or(0) > 0
+}
+
+fn pr_marker(set: &Set, opts: &FormatOptions) -> String {
+    if opts.annotate && is_pr(set) {
+        color_pr("★")
+    } else {
+        String::new()
+    }
+}
+
+// trailing "[e1RM 446, 91%]" for the strongest of the given sets
+fn annotation(sets: &[&Set], opts: &FormatOptions) -> String {
+    if !opts.annotate {
+        return String::new();
+    }
+    let mut parts = Vec::new();
+    let best = sets.iter()
+        .filter(|s| s.est1rm.unwrap_or(0.0) > 0.0)
+        .max_by(|a, b| a.est1rm.unwrap_or(0.0).total_cmp(&b.est1rm.unwrap_or(0.0)));
+    if let Some(best) = best {
+        let lb = best.lb.unwrap_or(0.0) == 1.0;
+        parts.push(format!("e1RM {}", format_weight(best.est1rm.unwrap_or(0.0), lb)));
+    }
+    let intensity = sets.iter()
+        .filter_map(|s| s.intensity_pct())
+        .max_by(|a, b| a.total_cmp(b));
+    if let Some(pct) = intensity {
+        parts.push(format!("{:.0}%", pct));
+    }
+    if parts.is_empty() {
+        String::new()
+    } else {
+        format!(" {}", color_annotation(&format!("[{}]", parts.join(", "))))
+    }
+}
+
+pub fn format_set_with(set: &Set, opts: &FormatOptions) -> String {
+    format_bare_set(set, opts) + &annotation(&[set], opts)
+}
+
+// sets printed on one line need at least this many to be written as a
+// ramp ("60..140/20 x 5", "100 x 5..2") instead of a list
+const RAMP_MIN: usize = 4;
+
+// plain weight x reps single sets, the only ones that share a line
+fn groupable(set: &Set) -> bool {
+    set.is_weight_reps() && !has_readings(set) && set.r.unwrap_or(0) > 0 && set.s.unwrap_or(1) == 1
+}
+
+fn has_comment(set: &Set) -> bool {
+    set.c.as_deref().is_some_and(|c| !c.is_empty())
+}
+
+// How many sets from the first on can share a line: same unit and kind, RPE
+// given for all of them or none, and a comment only on the last one, where
+// the parser puts a line's comment back.
+fn run_length(sets: &[Set], same: impl Fn(&Set, &Set) -> bool) -> usize {
+    let first = &sets[0];
+    if !groupable(first) {
+        return 1;
+    }
+    let mut n = 1;
+    while n < sets.len() {
+        let next = &sets[n];
+        if has_comment(&sets[n - 1]) || !groupable(next) || next.lb != first.lb || next.ubw != first.ubw
+            || next.rpe.is_some() != first.rpe.is_some() || !same(first, next) {
+            break;
+        }
+        n += 1;
+    }
+    n
+}
+
+// " @8" when the sets agree, " @7, 8, 9" when they do not
+fn format_rpes(sets: &[Set]) -> String {
+    let rpes: Vec<f32> = sets.iter().filter_map(|s| s.rpe).filter(|&r| r > 0.0).collect();
+    if rpes.len() != sets.len() {
+        return String::new();
+    }
+    if rpes.iter().all(|&r| r == rpes[0]) {
+        format!(" @{}", rpes[0])
+    } else {
+        format!(" @{}", rpes.iter().map(|r| r.to_string()).collect::<Vec<_>>().join(", "))
+    }
+}
+
+fn format_comment(set: &Set) -> String {
+    if has_comment(set) {
+        format!(" {}", set.c.as_deref().unwrap_or(""))
+    } else {
+        String::new()
+    }
+}
+
+// "60..140/20" for evenly spaced values, the step left out when it is 1
+fn format_ramp(values: &[f32], suffix: &str) -> Option<String> {
+    if values.len() < RAMP_MIN {
+        return None;
+    }
+    let step = values[1] - values[0];
+    if step == 0.0 || values.windows(2).any(|w| ((w[1] - w[0]) - step).abs() > 0.001) {
+        return None;
+    }
+    let mut ramp = format!("{}..{}", format_number(values[0]), format_number(values[values.len() - 1]));
+    if step.abs() != 1.0 {
+        ramp += &format!("/{}", format_number(step.abs()));
+    }
+    Some(ramp + suffix)
+}
+
+// per-value PR markers would be lost in a ramp
+fn any_marked(sets: &[Set], opts: &FormatOptions) -> bool {
+    opts.annotate && sets.iter().any(is_pr)
+}
+
+// identical sets: "100 x 5 x 3"
+fn format_identical(sets: &[Set], opts: &FormatOptions) -> String {
+    let first = &sets[0];
+    let marker = sets.iter().find(|s| is_pr(s)).map(|s| pr_marker(s, opts)).unwrap_or_default();
+    let refs: Vec<&Set> = sets.iter().collect();
+    format!("{} x {}{} x {}", color_weight(&format_load_with(first, opts)), color_reps(&first.r.unwrap_or(0).to_string()),
+        marker, color_sets(&sets.len().to_string()))
+        + &format_rpes(sets) + &annotation(&refs, opts) + &format_comment(&sets[sets.len() - 1])
+}
+
+// one weight, several rep counts: "135 x 5, 3" or "100 x 5..2"
+fn format_same_weight(sets: &[Set], opts: &FormatOptions) -> String {
+    let reps: Vec<f32> = sets.iter().map(|s| s.r.unwrap_or(0) as f32).collect();
+    let r_str = match format_ramp(&reps, "") {
+        Some(ramp) if !any_marked(sets, opts) => color_reps(&ramp),
+        _ => sets.iter()
+            .map(|s| color_reps(&s.r.unwrap_or(0).to_string()) + &pr_marker(s, opts))
+            .collect::<Vec<_>>().join(", "),
+    };
+    let refs: Vec<&Set> = sets.iter().collect();
+    format!("{} x {}", color_weight(&format_load_with(&sets[0], opts)), r_str)
+        + &format_rpes(sets) + &annotation(&refs, opts) + &format_comment(&sets[sets.len() - 1])
+}
+
+// several weights, one rep count: "135, 145 x 5" or "60..140/20 x 5"
+fn format_same_reps(sets: &[Set], opts: &FormatOptions) -> String {
+    let first = &sets[0];
+    let lb = first.lb.unwrap_or(0.0) == 1.0;
+    let values: Vec<f32> = sets.iter()
+        .map(|s| weight_text(s.w.unwrap_or(0.0), lb, opts).parse().unwrap_or(0.0))
+        .collect();
+    let ramp = format_ramp(&values, unit_suffix(lb, opts))
+        .filter(|_| !first.is_bodyweight() && !any_marked(sets, opts));
+    let w_str = match ramp {
+        Some(ramp) => color_weight(&ramp),
+        None => sets.iter()
+            .map(|s| color_weight(&format_load_with(s, opts)) + &pr_marker(s, opts))
+            .collect::<Vec<_>>().join(", "),
+    };
+    let refs: Vec<&Set> = sets.iter().collect();
+    format!("{} x {}", w_str, color_reps(&first.r.unwrap_or(0).to_string()))
+        + &format_rpes(sets) + &annotation(&refs, opts) + &format_comment(&sets[sets.len() - 1])
+}
+
 pub fn compress_sets(sets: &[Set]) -> Vec<String> {
+    compress_sets_with(sets, &FormatOptions::default())
+}
+
+// Writes a block's sets in as few lines as the log notation allows without
+// losing anything: identical sets first, then one weight with several rep
+// counts, then one rep count with several weights; anything else gets a line
+// of its own.
+pub fn compress_sets_with(sets: &[Set], opts: &FormatOptions) -> Vec<String> {
+    if opts.no_compress {
+        return sets.iter().map(|s| format_set_with(s, opts)).collect();
+    }
     let mut compressed = Vec::new();
     let mut i = 0;
     while i < sets.len() {
-        let set = &sets[i];
-        if set.set_type.unwrap_or(0) != 0 {
-            compressed.push(format_set(set));
-            i += 1;
+        let rest = &sets[i..];
+        // "x 3" reads as all three sets, so a commented set stays out
+        let mut n = run_length(rest, |a, b| a.w == b.w && a.r == b.r);
+        if n > 1 && has_comment(&rest[n - 1]) {
+            n -= 1;
+        }
+        if n > 1 {
+            compressed.push(format_identical(&rest[..n], opts));
+            i += n;
             continue;
         }
-        let w = set.w.unwrap_or(0.0);
-        let r = set.r.unwrap_or(0);
-        let _s = set.s.unwrap_or(1);
-        let rpe = set.rpe.unwrap_or(0.0);
-        let lb = set.lb.unwrap_or(0.0) == 1.0;
-        // check for same weight consecutive
-        let mut same_weight = vec![r];
-        let mut j = i + 1;
-        while j < sets.len() {
-            let next = &sets[j];
-            if next.set_type.unwrap_or(0) != 0 || next.w != set.w || next.rpe != set.rpe || next.lb != set.lb || next.s != set.s {
-                break;
-            }
-            same_weight.push(next.r.unwrap_or(0));
-            j += 1;
-        }
-        if same_weight.len() > 1 {
-            let w_str = color_weight(&format_weight(w, lb));
-            let r_str = same_weight.iter().map(|&r| color_reps(&r.to_string())).collect::<Vec<_>>().join(", ");
-            let mut line = format!("{} x {}", w_str, r_str);
-            if rpe > 0.0 {
-                line += &format!(" @{}", rpe);
-            }
-            compressed.push(line);
-            i = j;
-        } else {
-            // check for same rep
-            let mut same_rep = vec![w];
-            let mut j = i + 1;
-            while j < sets.len() {
-                let next = &sets[j];
-                if next.set_type.unwrap_or(0) != 0 || next.r != set.r || next.rpe != set.rpe || next.lb != set.lb || next.s != set.s {
-                    break;
-                }
-                same_rep.push(next.w.unwrap_or(0.0));
-                j += 1;
-            }
-            if same_rep.len() > 1 {
-                let w_str = same_rep.iter().map(|&w| color_weight(&format_weight(w, lb))).collect::<Vec<_>>().join(", ");
-                let r_str = color_reps(&r.to_string());
-                let mut line = format!("{} x {}", w_str, r_str);
-                if rpe > 0.0 {
-                    line += &format!(" @{}", rpe);
-                }
-                compressed.push(line);
-                i = j;
-            } else {
-                compressed.push(format_set(set));
-                i += 1;
-            }
+        let n = run_length(rest, |a, b| a.w == b.w);
+        if n > 1 {
+            compressed.push(format_same_weight(&rest[..n], opts));
+            i += n;
+            continue;
+        }
+        let n = run_length(rest, |a, b| a.r == b.r);
+        if n > 1 {
+            compressed.push(format_same_reps(&rest[..n], opts));
+            i += n;
+            continue;
         }
+        compressed.push(format_set_with(&rest[0], opts));
+        i += 1;
     }
     compressed
 }
 
 pub fn format_single_eblock(jday: &JDay, eblock: &EBlock) -> String {
+    format_single_eblock_with(jday, eblock, &FormatOptions::default())
+}
+
+pub fn format_single_eblock_with(jday: &JDay, eblock: &EBlock, opts: &FormatOptions) -> String {
     let mut ex_map: HashMap<String, &Exercise> = HashMap::new();
     for ex_wrap in &jday.exercises {
         ex_map.insert(ex_wrap.exercise.id.clone(), &ex_wrap.exercise);
@@ -185,14 +626,176 @@ pub fn format_single_eblock(jday: &JDay, eblock: &EBlock) -> String {
     let mut lines = Vec::new();
     if let Some(ex) = ex_map.get(&eblock.eid) {
         lines.push("#".to_string() + &color_exercise(&ex.name));
-        lines.extend(compress_sets(&eblock.sets));
+        lines.extend(compress_sets_with(&eblock.sets, opts));
     }
     lines.join("\n")
 }
 
-
+// best effort of a time/distance block: longest distance (then fastest time),
+// or longest time when nothing was covered
+fn summarize_time_distance(sets: &[Set]) -> Option<String> {
+    let best = if sets.iter().any(|s| s.has_distance()) {
+        sets.iter()
+            .filter(|s| s.has_distance())
+            .max_by(|a, b| {
+                a.distance_m().total_cmp(&b.distance_m())
+                    .then(a.w.unwrap_or(0.0).total_cmp(&b.w.unwrap_or(0.0)))
+                    .then(b.t.unwrap_or(0.0).total_cmp(&a.t.unwrap_or(0.0)))
+            })?
+    } else {
+        sets.iter()
+            .filter(|s| s.has_time())
+            .max_by(|a, b| {
+                a.t.unwrap_or(0.0).total_cmp(&b.t.unwrap_or(0.0))
+                    .then(a.w.unwrap_or(0.0).total_cmp(&b.w.unwrap_or(0.0)))
+            })?
+    };
+    let mut parts = Vec::new();
+    if best.w.unwrap_or(0.0) != 0.0 || best.is_bodyweight() {
+        parts.push(color_weight(&format_load(best)));
+    }
+    let line = if best.has_distance() {
+        parts.push(color_distance(&format_distance(best)));
+        let mut line = parts.join("x");
+        if best.has_time() {
+            line += " in ";
+            line += &color_duration(&format_duration(best.t.unwrap_or(0.0)));
+        }
+        line
+    } else {
+        parts.push(color_duration(&format_duration(best.t.unwrap_or(0.0))));
+        parts.join("x")
+    };
+    Some(line)
+}
 
 pub fn summarize_workout(jday: &JDay) -> String {
+    summarize_workout_by(jday, SummaryMode::Top)
+}
+
+// Sets of a bodyweight block with only the added load in `w`. The server
+// sends the total, bodyweight included, so the day's bodyweight comes off;
+// sets read from a log are already marked and left alone.
+fn bodyweight_sets(sets: &[Set], bw: Option<f32>) -> Vec<Set> {
+    let bw = bw.unwrap_or(0.0);
+    sets.iter()
+        .map(|s| match s.w {
+            Some(w) if !s.is_bodyweight() && w > 0.0 && bw > 0.0 => {
+                let added = if (w - bw).abs() < 0.01 { 0.0 } else { w - bw };
+                Set { w: Some(added), ubw: Some(1), ..s.clone() }
+            }
+            _ => Set { ubw: Some(1), ..s.clone() },
+        })
+        .collect()
+}
+
+// bodyweight work: most added load, then most reps, e.g. "BWx15" or "BW+25x5"
+fn summarize_bodyweight(sets: &[Set]) -> Option<String> {
+    let best = sets.iter()
+        .filter(|s| s.r.unwrap_or(0) > 0)
+        .max_by(|a, b| {
+            a.w.unwrap_or(0.0).total_cmp(&b.w.unwrap_or(0.0))
+                .then(a.r.unwrap_or(0).cmp(&b.r.unwrap_or(0)))
+        })?;
+    Some(format!("{}x{}", color_weight(&format_load(best)), color_reps(&best.r.unwrap_or(0).to_string())))
+}
+
+// heaviest set: max weight, then max reps
+fn summarize_top(kind: BlockKind, sets: &[Set]) -> Option<String> {
+    match kind {
+        BlockKind::Timed | BlockKind::Distance => return summarize_time_distance(sets),
+        BlockKind::Bodyweight => return summarize_bodyweight(sets),
+        BlockKind::Weighted => {}
+    }
+    let mut max_weight = 0.0;
+    let mut max_reps = 0;
+    for set in sets {
+        let w = set.w.unwrap_or(0.0);
+        let r = set.r.unwrap_or(0);
+        if w > max_weight || (w == max_weight && r > max_reps) {
+            max_weight = w;
+            max_reps = r;
+        }
+    }
+    if max_weight > 0.0 {
+        let lb = sets.iter().any(|s| s.lb.unwrap_or(0.0) == 1.0);
+        let w_str = color_weight(&format_weight(max_weight, lb));
+        let r_str = color_reps(&max_reps.to_string());
+        Some(format!("{}x{}", w_str, r_str))
+    } else {
+        None
+    }
+}
+
+// "1 set", "4 sets", with only the count colored
+fn format_set_count(count: u32) -> String {
+    format!("{} {}", color_sets(&count.to_string()), if count == 1 { "set" } else { "sets" })
+}
+
+fn summarize_metric(mode: SummaryMode, kind: BlockKind, sets: &[Set], lb: bool) -> Option<String> {
+    match mode {
+        SummaryMode::Top => summarize_top(kind, sets),
+        SummaryMode::E1rm => {
+            let e1rm = metrics::best_e1rm(sets);
+            (e1rm > 0.0).then(|| format!("e1RM {}", color_weight(&format_weight(e1rm, lb))))
+        }
+        SummaryMode::Volume => {
+            let volume = metrics::total_volume(sets);
+            (volume > 0.0).then(|| format!("vol {}", color_weight(&format_weight(volume, lb))))
+        }
+        SummaryMode::Reps => {
+            let reps = metrics::total_reps(sets);
+            (reps > 0).then(|| format!("{} reps", color_reps(&reps.to_string())))
+        }
+        SummaryMode::Sets => {
+            let count = metrics::working_sets(sets);
+            (count > 0).then(|| format_set_count(count))
+        }
+        SummaryMode::All => {
+            let parts: Vec<String> = [SummaryMode::Top, SummaryMode::E1rm, SummaryMode::Volume, SummaryMode::Reps, SummaryMode::Sets]
+                .iter()
+                .filter_map(|&m| summarize_metric(m, kind, sets, lb))
+                .collect();
+            (!parts.is_empty()).then(|| parts.join(" "))
+        }
+    }
+}
+
+// workout-wide total appended after the per-exercise summaries; top sets and
+// e1RMs of different lifts don't add up, so those have none
+fn summarize_total(mode: SummaryMode, jday: &JDay, lb: bool) -> Option<String> {
+    match mode {
+        SummaryMode::Top | SummaryMode::E1rm => None,
+        SummaryMode::All => {
+            let parts: Vec<String> = [SummaryMode::Volume, SummaryMode::Reps, SummaryMode::Sets]
+                .iter()
+                .filter_map(|&m| summarize_total(m, jday, lb))
+                .collect();
+            (!parts.is_empty()).then(|| parts.join(" "))
+        }
+        SummaryMode::Volume | SummaryMode::Reps => {
+            let sets: Vec<Set> = jday.eblocks.iter().flat_map(|b| summary_sets(jday, b)).collect();
+            summarize_metric(mode, BlockKind::Weighted, &sets, lb)
+        }
+        SummaryMode::Sets => {
+            // working sets are relative to each block's top set
+            let count: u32 = jday.eblocks.iter().map(|b| metrics::working_sets(&summary_sets(jday, b))).sum();
+            (count > 0).then(|| format_set_count(count))
+        }
+    }
+}
+
+// a block's sets as the summaries count them: bodyweight blocks keep only
+// the added load
+fn summary_sets(jday: &JDay, eblock: &EBlock) -> Vec<Set> {
+    match jday.exercise(&eblock.eid) {
+        Some(ex) if metrics::classify(ex, &eblock.sets) == BlockKind::Bodyweight => bodyweight_sets(&eblock.sets, jday.bw),
+        _ => eblock.sets.clone(),
+    }
+}
+
+// "#name  summary" for every block that has something to report
+fn block_summaries(jday: &JDay, mode: SummaryMode) -> Vec<(&EBlock, String)> {
     let mut ex_map: HashMap<String, &Exercise> = HashMap::new();
     for ex_wrap in &jday.exercises {
         ex_map.insert(ex_wrap.exercise.id.clone(), &ex_wrap.exercise);
@@ -200,43 +803,100 @@ pub fn summarize_workout(jday: &JDay) -> String {
     let mut summaries = Vec::new();
     for eblock in &jday.eblocks {
         if let Some(ex) = ex_map.get(&eblock.eid) {
-            // Find the heaviest set: max weight, then max reps
-            let mut max_weight = 0.0;
-            let mut max_reps = 0;
-            for set in &eblock.sets {
-                let w = set.w.unwrap_or(0.0);
-                let r = set.r.unwrap_or(0);
-                if w > max_weight || (w == max_weight && r > max_reps) {
-                    max_weight = w;
-                    max_reps = r;
-                }
-            }
-            if max_weight > 0.0 {
-                let lb = eblock.sets.iter().any(|s| s.lb.unwrap_or(0.0) == 1.0);
-                let w_str = color_weight(&format_weight(max_weight, lb));
-                let r_str = color_reps(&max_reps.to_string());
-                summaries.push(format!("#{}  {}x{}", color_exercise(&ex.name), w_str, r_str));
+            let lb = eblock.sets.iter().any(|s| s.lb.unwrap_or(0.0) == 1.0);
+            let kind = metrics::classify(ex, &eblock.sets);
+            let sets = summary_sets(jday, eblock);
+            if let Some(summary) = summarize_metric(mode, kind, &sets, lb) {
+                summaries.push((eblock, format!("#{}  {}", color_exercise(&ex.name), summary)));
             }
         }
     }
+    summaries
+}
+
+fn total_summary(jday: &JDay, mode: SummaryMode) -> Option<String> {
+    let lb = jday.eblocks.iter().flat_map(|b| &b.sets).any(|s| s.lb.unwrap_or(0.0) == 1.0);
+    summarize_total(mode, jday, lb).map(|total| format!("total  {}", total))
+}
+
+pub fn summarize_workout_by(jday: &JDay, mode: SummaryMode) -> String {
+    let mut summaries: Vec<String> = block_summaries(jday, mode).into_iter().map(|(_, s)| s).collect();
+    summaries.extend(total_summary(jday, mode));
     summaries.join("; ")
 }
 
-pub fn format_workout(jday: &JDay) -> String {
-    let mut result = jday.log.clone();
-    for eblock in &jday.eblocks {
-        let formatted = format_single_eblock(jday, eblock);
-        let placeholder = format!("EBLOCK:{}", eblock.eid);
-        result = result.replace(&placeholder, &formatted);
+// blocks gathered under the label `group` gives them, labels in order of
+// first appearance: "squat: #sq  405x3, #box  315x5; hinge: #dl  455x2"
+pub fn summarize_workout_grouped(jday: &JDay, mode: SummaryMode, group: impl Fn(&EBlock) -> String) -> String {
+    let mut groups: Vec<(String, Vec<String>)> = Vec::new();
+    for (eblock, summary) in block_summaries(jday, mode) {
+        let label = group(eblock);
+        match groups.iter_mut().find(|(l, _)| *l == label) {
+            Some((_, members)) => members.push(summary),
+            None => groups.push((label, vec![summary])),
+        }
     }
-    result
+    let mut parts: Vec<String> = groups.into_iter()
+        .map(|(label, members)| format!("{}: {}", label, members.join(", ")))
+        .collect();
+    parts.extend(total_summary(jday, mode));
+    parts.join("; ")
+}
+
+pub fn format_workout(jday: &JDay) -> String {
+    format_workout_with(jday, &FormatOptions::default())
+}
+
+pub fn format_workout_with(jday: &JDay, opts: &FormatOptions) -> String {
+    // placeholders sit on lines of their own; matching whole lines keeps
+    // "EBLOCK:1" from eating into "EBLOCK:12". An exercise done twice has
+    // two placeholders, taken by its blocks in order, and when only "//"
+    // comments stand between them the second goes on without a header.
+    let mut used = vec![false; jday.eblocks.len()];
+    let mut open: Option<&str> = None;
+    jday.log.split('\n')
+        .map(|line| {
+            let found = (0..jday.eblocks.len())
+                .find(|&i| !used[i] && line.trim() == format!("EBLOCK:{}", jday.eblocks[i].eid));
+            match found {
+                Some(i) => {
+                    used[i] = true;
+                    let block = &jday.eblocks[i];
+                    let continued = open == Some(block.eid.as_str());
+                    open = Some(&block.eid);
+                    if continued {
+                        compress_sets_with(&block.sets, opts).join("\n")
+                    } else {
+                        format_single_eblock_with(jday, block, opts)
+                    }
+                }
+                None => {
+                    if !line.trim_start().starts_with("//") {
+                        open = None;
+                    }
+                    line.to_string()
+                }
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
 }
 
 pub fn render_workout(date: &str, jday: &JDay, user: &User) -> String {
-    let formatted = format_workout(jday);
-    let mut bw = jday.bw.unwrap_or(0.0);
+    render_workout_with(date, jday, user, &FormatOptions::default())
+}
+
+// bodyweight arrives in kg; show it in the user's unit
+pub fn user_bw(bw: f32, user: &User) -> f32 {
     if user.usekg.unwrap_or(1) != 1 {
-        bw *= 2.20462; // convert kg to lb
+        bw * LB_PER_KG
+    } else {
+        bw
     }
+}
+
+pub fn render_workout_with(date: &str, jday: &JDay, user: &User, opts: &FormatOptions) -> String {
+    let formatted = format_workout_with(jday, opts);
+    let bw = user_bw(jday.bw.unwrap_or(0.0), user);
     format!("{}\n@ {} bw\n{}", color_date(date), color_bw(&format!("{:.0}", bw)), formatted)
-}
\ No newline at end of file
+}
@@ -0,0 +1,49 @@
+use crate::aliases::{self, Aliases};
+use crate::formatters;
+use crate::models::JDay;
+
+// one block of the exercise in one workout
+pub struct Entry {
+    pub date: String,
+    pub exercise: String,
+    pub sets: Vec<String>,
+}
+
+// `days` are (YYYY-MM-DD, workout) pairs, oldest first; entries come back
+// newest first. With `aliases`, the query also picks up every variant
+// grouped under it.
+pub fn exercise_history_with(days: &[(String, JDay)], query: &str, aliases: Option<&Aliases>) -> Vec<Entry> {
+    let mut entries = Vec::new();
+    for (date, jday) in days.iter().rev() {
+        for eblock in jday.eblocks.iter().filter(|b| aliases::block_matches(jday, b, query, aliases)) {
+            let Some(ex) = jday.exercise(&eblock.eid) else {
+                continue;
+            };
+            entries.push(Entry {
+                date: date.clone(),
+                exercise: ex.name.clone(),
+                sets: formatters::compress_sets(&eblock.sets),
+            });
+        }
+    }
+    entries
+}
+
+// the exercise column only shows up when a tag matched more than one exercise
+pub fn render_history(entries: &[Entry]) -> String {
+    let several = entries.iter().any(|e| e.exercise != entries[0].exercise);
+    let mut headers = vec!["date", "sets"];
+    if several {
+        headers.insert(1, "exercise");
+    }
+    let rows: Vec<Vec<String>> = entries.iter()
+        .map(|e| {
+            let mut row = vec![formatters::color_date(&e.date), e.sets.join("; ")];
+            if several {
+                row.insert(1, format!("#{}", formatters::color_exercise(&e.exercise)));
+            }
+            row
+        })
+        .collect();
+    formatters::render_table(&headers, &rows)
+}
@@ -3,4 +3,27 @@ pub mod formatters;
 pub mod auth;
 pub mod api;
 pub mod workouts;
-pub mod utils;
\ No newline at end of file
+pub mod utils;
+pub mod metrics;
+pub mod pager;
+pub mod diff;
+pub mod stats;
+pub mod records;
+pub mod charts;
+pub mod trend;
+pub mod bodyweight;
+pub mod calendar;
+pub mod config;
+pub mod streaks;
+pub mod history;
+pub mod search;
+pub mod load;
+pub mod rpe;
+pub mod report;
+pub mod muscles;
+pub mod aliases;
+pub mod compare;
+pub mod zones;
+pub mod parser;
+pub mod logfmt;
+pub mod source;
\ No newline at end of file
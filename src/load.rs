@@ -0,0 +1,122 @@
+use std::collections::BTreeMap;
+use chrono::NaiveDate;
+
+use crate::formatters;
+use crate::metrics;
+use crate::models::JDay;
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LoadBasis {
+    // w x r x s of the whole workout
+    #[default]
+    Tonnage,
+    // working sets x RPE
+    Rpe,
+}
+
+// days of history the chronic window needs before the first reported day
+pub const CHRONIC_DAYS: i64 = 28;
+const ACUTE_DAYS: usize = 7;
+
+// acute:chronic above this is a spike worth a deload look
+pub const ACWR_SPIKE: f32 = 1.5;
+pub const ACWR_HIGH: f32 = 1.3;
+// the same load day after day
+pub const MONOTONY_HIGH: f32 = 2.0;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadDay {
+    pub date: NaiveDate,
+    pub load: f32,
+    // mean daily load over the last 7 and 28 days
+    pub acute: f32,
+    pub chronic: f32,
+    pub acwr: Option<f32>,
+    // mean / standard deviation of the last 7 days, and weekly load x monotony
+    pub monotony: Option<f32>,
+    pub strain: Option<f32>,
+}
+
+pub fn session_load(jday: &JDay, basis: LoadBasis) -> f32 {
+    jday.eblocks.iter()
+        .map(|b| match basis {
+            LoadBasis::Tonnage => metrics::total_volume(&b.sets),
+            LoadBasis::Rpe => metrics::rpe_load(&b.sets),
+        })
+        .sum()
+}
+
+// one load per calendar day from `oldest` to `latest`, rest days at 0
+pub fn daily_loads(days: &[(String, JDay)], basis: LoadBasis, oldest: NaiveDate, latest: NaiveDate) -> Vec<(NaiveDate, f32)> {
+    let mut by_date: BTreeMap<NaiveDate, f32> = BTreeMap::new();
+    for (date, jday) in days {
+        if let Ok(day) = date.parse() {
+            *by_date.entry(day).or_default() += session_load(jday, basis);
+        }
+    }
+    oldest.iter_days()
+        .take_while(|d| *d <= latest)
+        .map(|d| (d, by_date.get(&d).copied().unwrap_or(0.0)))
+        .collect()
+}
+
+fn mean(values: &[f32]) -> f32 {
+    values.iter().sum::<f32>() / values.len().max(1) as f32
+}
+
+// `loads` are consecutive days; rows start at `from`, earlier days only feed
+// the rolling windows. Ratios need full windows behind them.
+pub fn workload(loads: &[(NaiveDate, f32)], from: NaiveDate) -> Vec<LoadDay> {
+    let values: Vec<f32> = loads.iter().map(|(_, l)| *l).collect();
+    let mut out = Vec::new();
+    for (i, &(date, load)) in loads.iter().enumerate() {
+        if date < from {
+            continue;
+        }
+        let acute_window = &values[(i + 1).saturating_sub(ACUTE_DAYS)..=i];
+        let chronic_window = &values[(i + 1).saturating_sub(CHRONIC_DAYS as usize)..=i];
+        let acute = mean(acute_window);
+        let chronic = mean(chronic_window);
+        let acwr = (chronic_window.len() == CHRONIC_DAYS as usize && chronic > 0.0).then(|| acute / chronic);
+        let monotony = if acute_window.len() == ACUTE_DAYS {
+            let sd = (acute_window.iter().map(|v| (v - acute).powi(2)).sum::<f32>() / ACUTE_DAYS as f32).sqrt();
+            (sd > 0.0).then(|| acute / sd)
+        } else {
+            None
+        };
+        let strain = monotony.map(|m| acute * ACUTE_DAYS as f32 * m);
+        out.push(LoadDay { date, load, acute, chronic, acwr, monotony, strain });
+    }
+    out
+}
+
+pub fn flag(day: &LoadDay) -> Option<&'static str> {
+    match (day.acwr, day.monotony) {
+        (Some(r), _) if r > ACWR_SPIKE => Some("spike"),
+        (Some(r), _) if r > ACWR_HIGH => Some("high"),
+        (_, Some(m)) if m > MONOTONY_HIGH => Some("monotony"),
+        _ => None,
+    }
+}
+
+pub fn render_load(days: &[LoadDay], basis: LoadBasis, lb: bool) -> String {
+    let amount = |v: f32| match basis {
+        LoadBasis::Tonnage => formatters::format_weight(v, lb),
+        LoadBasis::Rpe => format!("{:.0}", v),
+    };
+    let ratio = |v: Option<f32>| v.map(|v| format!("{:.2}", v)).unwrap_or_default();
+    let rows: Vec<Vec<String>> = days.iter()
+        .map(|d| vec![
+            formatters::color_date(&d.date.to_string()),
+            if d.load > 0.0 { amount(d.load) } else { String::new() },
+            amount(d.acute),
+            amount(d.chronic),
+            ratio(d.acwr),
+            ratio(d.monotony),
+            d.strain.map(amount).unwrap_or_default(),
+            flag(d).map(|f| formatters::color_delta(f, -1.0)).unwrap_or_default(),
+        ])
+        .collect();
+    let headers = ["date", "load", "acute", "chronic", "ACWR", "monotony", "strain", ""];
+    formatters::render_table(&headers, &rows)
+}
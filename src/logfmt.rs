@@ -0,0 +1,42 @@
+use crate::formatters::{self, FormatOptions};
+use crate::parser::{self, ParseError, Unit};
+
+// The canonical form of a log file: date and bodyweight lines first, blocks
+// written the way `show` prints them (grouped as by compress_sets) with exact
+// weights in the file's unit, free text and comments kept as written minus
+// trailing whitespace. Colors must be off.
+pub fn format_log(text: &str, unit: Unit) -> Result<String, ParseError> {
+    let workout = parser::parse(text, unit)?;
+    let lb = unit == Unit::Lb;
+    let opts = FormatOptions { log_unit: Some(lb), ..Default::default() };
+    let mut lines = Vec::new();
+    if let Some(date) = &workout.date {
+        lines.push(date.clone());
+    }
+    if let Some(bw) = workout.jday.bw {
+        lines.push(format!("@ {} bw", formatters::format_number(if lb { bw * formatters::LB_PER_KG } else { bw })));
+    }
+    let body = formatters::format_workout_with(&workout.jday, &opts);
+    lines.extend(body.lines().map(|l| l.trim_end().to_string()));
+    Ok(lines.join("\n").trim_matches('\n').to_string() + "\n")
+}
+
+// `--check` output: the changed lines between the first and last difference,
+// or None when the file is already formatted
+pub fn render_check(path: &str, original: &str, formatted: &str) -> Option<String> {
+    if original == formatted {
+        return None;
+    }
+    let a: Vec<&str> = original.lines().collect();
+    let b: Vec<&str> = formatted.lines().collect();
+    let prefix = a.iter().zip(&b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..].iter().rev().zip(b[prefix..].iter().rev()).take_while(|(x, y)| x == y).count();
+    let mut out = vec![format!("Diff in {} at line {}:", path, prefix + 1)];
+    out.extend(a[prefix..a.len() - suffix].iter().map(|l| format!("-{}", l)));
+    out.extend(b[prefix..b.len() - suffix].iter().map(|l| format!("+{}", l)));
+    if a.len() == b.len() && prefix == a.len() {
+        // only the trailing newline differs
+        out.push("\\ newline at end of file".to_string());
+    }
+    Some(out.join("\n"))
+}
@@ -1,13 +1,14 @@
-mod models;
-mod formatters;
-mod auth;
-mod api;
-mod workouts;
-mod utils;
+use std::cell::LazyCell;
 
+use chrono::Datelike;
 use clap::{Parser, Subcommand};
 
-use crate::api::{ReqwestClient, ApiClient};
+use wxrust::api::ReqwestClient;
+use wxrust::source::WorkoutSource;
+use wxrust::{
+    aliases, auth, bodyweight, calendar, compare, config, diff, formatters, history, load, logfmt, metrics,
+    models, muscles, pager, parser, records, report, rpe, search, source, stats, streaks, trend, utils, zones,
+};
 
 #[derive(Parser)]
 #[command(name = "wxrust")]
@@ -25,6 +26,15 @@ struct Args {
     #[arg(short, long)]
     verbose: bool,
 
+    #[arg(long = "no-pager")]
+    no_pager: bool,
+
+    #[arg(long)]
+    config: Option<String>,
+
+    #[arg(long)]
+    source: Option<String>,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -33,6 +43,23 @@ struct Args {
 enum Commands {
     List(ListArgs),
     Show(ShowArgs),
+    Diff(DiffArgs),
+    Stats(StatsArgs),
+    Prs(PrsArgs),
+    Trend(TrendArgs),
+    Bw(BwArgs),
+    Calendar(CalendarArgs),
+    Streaks(StreaksArgs),
+    Exercise(ExerciseArgs),
+    Search(SearchArgs),
+    Load(LoadArgs),
+    Rpe(RpeArgs),
+    Report(ReportArgs),
+    Volume(VolumeArgs),
+    Compare(CompareArgs),
+    Zones(ZonesArgs),
+    Parse(ParseArgs),
+    Fmt(FmtArgs),
 }
 
 #[derive(Parser)]
@@ -40,8 +67,8 @@ struct ListArgs {
     #[arg(short, long)]
     details: bool,
 
-    #[arg(short, long)]
-    summary: bool,
+    #[arg(short, long, num_args = 0..=1, require_equals = true, default_missing_value = "top")]
+    summary: Option<formatters::SummaryMode>,
 
     #[arg(short, long)]
     reverse: bool,
@@ -55,45 +82,316 @@ struct ListArgs {
     #[arg(short, long)]
     count: Option<u32>,
 
+    #[arg(long)]
+    annotate: bool,
+
+    #[arg(long = "no-compress")]
+    no_compress: bool,
+
+    #[arg(long = "by-pattern")]
+    by_pattern: bool,
+
     dates: Vec<String>,
 }
 
 #[derive(Parser)]
 struct ShowArgs {
+    #[arg(short, long, num_args = 0..=1, require_equals = true, default_missing_value = "top")]
+    summary: Option<formatters::SummaryMode>,
+
+    #[arg(long)]
+    annotate: bool,
+
+    #[arg(long = "no-compress")]
+    no_compress: bool,
+
+    #[arg(long = "by-pattern")]
+    by_pattern: bool,
+
+    date: Option<String>,
+}
+
+#[derive(Parser)]
+struct DiffArgs {
+    #[arg(short, long)]
+    exercise: Option<String>,
+
+    date_a: String,
+
+    date_b: String,
+}
+
+#[derive(Parser)]
+struct StatsArgs {
+    #[arg(short, long, value_enum, default_value = "week")]
+    by: stats::GroupBy,
+
+    #[arg(long = "no-group")]
+    no_group: bool,
+
+    range: String,
+}
+
+#[derive(Parser)]
+struct PrsArgs {
+    #[arg(short, long)]
+    exercise: Option<String>,
+
+    #[arg(short, long)]
+    range: Option<String>,
+
+    #[arg(long = "no-group")]
+    no_group: bool,
+}
+
+#[derive(Parser)]
+struct TrendArgs {
+    #[arg(short, long, value_enum, default_value = "server")]
+    formula: metrics::Formula,
+
+    #[arg(long = "no-group")]
+    no_group: bool,
+
+    exercise: String,
+
+    range: String,
+}
+
+#[derive(Parser)]
+struct BwArgs {
+    range: String,
+}
+
+#[derive(Parser)]
+struct CalendarArgs {
+    #[arg(short, long, value_enum, default_value = "presence")]
+    metric: calendar::Metric,
+
+    year: Option<i32>,
+}
+
+#[derive(Parser)]
+struct StreaksArgs {
+    #[arg(short, long)]
+    target: Option<f32>,
+}
+
+#[derive(Parser)]
+struct ExerciseArgs {
+    #[arg(long = "no-group")]
+    no_group: bool,
+
+    exercise: String,
+
+    range: Option<String>,
+}
+
+#[derive(Parser)]
+struct SearchArgs {
+    #[arg(short, long)]
+    ignore_case: bool,
+
+    #[arg(short = 'C', long, default_value_t = 0)]
+    context: usize,
+
+    pattern: String,
+
+    range: Option<String>,
+}
+
+#[derive(Parser)]
+struct LoadArgs {
+    #[arg(short, long, value_enum, default_value = "tonnage")]
+    by: load::LoadBasis,
+
+    range: String,
+}
+
+#[derive(Parser)]
+struct RpeArgs {
+    #[arg(short, long)]
+    exercise: Option<String>,
+
+    #[arg(short, long)]
+    table: bool,
+
+    range: Option<String>,
+}
+
+#[derive(Parser)]
+#[command(group(clap::ArgGroup::new("period").args(["week", "month", "year"])))]
+struct ReportArgs {
+    #[arg(short, long)]
+    week: bool,
+
     #[arg(short, long)]
-    summary: bool,
+    month: bool,
+
+    #[arg(short, long)]
+    year: bool,
+
+    #[arg(long)]
+    markdown: bool,
 
     date: Option<String>,
 }
 
+#[derive(Parser)]
+struct VolumeArgs {
+    #[arg(short, long, value_enum, default_value = "muscle")]
+    by: muscles::VolumeBy,
+
+    range: String,
+}
+
+#[derive(Parser)]
+struct CompareArgs {
+    #[arg(long = "no-group")]
+    no_group: bool,
+
+    range_a: String,
+
+    range_b: String,
+}
+
+#[derive(Parser)]
+struct ZonesArgs {
+    #[arg(short, long, value_enum, default_value = "e1rm")]
+    reference: zones::Reference,
+
+    exercise: String,
+
+    range: String,
+}
+
+#[derive(Parser)]
+struct ParseArgs {
+    #[arg(short, long, value_enum)]
+    unit: Option<parser::Unit>,
+
+    file: String,
+}
+
+#[derive(Parser)]
+struct FmtArgs {
+    #[arg(short, long, value_enum)]
+    unit: Option<parser::Unit>,
+
+    #[arg(long)]
+    check: bool,
+
+    #[arg(required = true)]
+    files: Vec<String>,
+}
+
+// report an error and exit with status 1, after the output printed so far
+fn fail(message: impl std::fmt::Display) -> ! {
+    pager::flush();
+    eprintln!("{}", message);
+    std::process::exit(1);
+}
+
+fn exit_on_error<T, E: std::fmt::Display>(result: Result<T, E>) -> T {
+    result.unwrap_or_else(|e| fail(e))
+}
+
+// normalize any date the range parser accepts to YYYY-MM-DD
+fn parse_day(s: &str) -> String {
+    match utils::parse_date_boundary(s, false) {
+        Ok(d) => d.to_string(),
+        Err(e) => fail(format!("Invalid date '{}': {}", s, e)),
+    }
+}
+
+#[cfg_attr(tarpaulin, ignore)]
+async fn connect(args: &Args, token_path: &str) -> (ReqwestClient, String) {
+    let client = ReqwestClient::new_with_verbose(args.verbose);
+    let token = exit_on_error(auth::login(&client, &args.credentials, token_path).await);
+    (client, token)
+}
+
+// --source: the API (logging in) by default, or "dir:<path>" for local logs
+// in the `unit` the config gives
+#[cfg_attr(tarpaulin, ignore)]
+async fn open_source(args: &Args, token_path: &str, unit: impl FnOnce() -> parser::Unit) -> Box<dyn WorkoutSource> {
+    match args.source.as_deref() {
+        None | Some("api") => {
+            let (client, token) = connect(args, token_path).await;
+            Box::new(source::ApiSource::new(client, token))
+        }
+        Some(spec) => match spec.strip_prefix("dir:") {
+            Some(dir) => Box::new(exit_on_error(source::DirSource::open(dir, unit()))),
+            None => fail(format!("Invalid source '{}': expected api or dir:<path>", spec)),
+        },
+    }
+}
+
+// every workout in a date range (all of them without one), oldest first;
+// days that fail to load are reported and skipped
+#[cfg_attr(tarpaulin, ignore)]
+async fn fetch_range(source: &dyn WorkoutSource, range: Option<&str>) -> Vec<(String, models::JDay)> {
+    let dates = match range {
+        Some(range) => {
+            let (oldest, latest) = match utils::parse_date_range(range) {
+                Ok(start_end) => start_end,
+                Err(e) => fail(format!("Invalid date range '{}': {}", range, e)),
+            };
+            exit_on_error(source.dates_in_range(oldest, latest).await)
+        }
+        None => exit_on_error(source.dates(None, None, 10000).await),
+    };
+    if dates.is_empty() {
+        fail("No workouts found in the specified range");
+    }
+    fetch_days(source, &dates).await
+}
+
+// the workouts of the given dates; days that fail to load are reported and skipped
+#[cfg_attr(tarpaulin, ignore)]
+async fn fetch_days(source: &dyn WorkoutSource, dates: &[String]) -> Vec<(String, models::JDay)> {
+    let mut days = Vec::new();
+    for (date, result) in source.workouts(dates).await {
+        match result {
+            Ok(jday) => days.push((date, jday)),
+            Err(e) => eprintln!("Error getting workout for {}: {}", date, e),
+        }
+    }
+    days
+}
 
+// the workouts of a period; unlike fetch_range an empty period is not an error
+#[cfg_attr(tarpaulin, ignore)]
+async fn fetch_period(source: &dyn WorkoutSource, oldest: chrono::NaiveDate, latest: chrono::NaiveDate) -> Vec<(String, models::JDay)> {
+    let dates = exit_on_error(source.dates_in_range(oldest, latest).await);
+    fetch_days(source, &dates).await
+}
 
 #[cfg_attr(tarpaulin, ignore)]
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args = Args::parse();
-    unsafe { std::env::set_var("WXRUST_COLOR", &args.color); }
+    let plain = match &args.command {
+        Commands::Report(report_args) => report_args.markdown,
+        // fmt writes log files, never escape codes
+        Commands::Fmt(_) => true,
+        _ => false,
+    };
+    formatters::set_color(if plain { "never" } else { &args.color });
 
     let home = std::env::var("HOME").unwrap_or(".".to_string());
     let token_path = format!("{}/.config/wxrust/token", home);
+    // read on first use, so commands that ignore the config never fail on it
+    let config = LazyCell::new(|| exit_on_error(config::load(args.config.as_deref(), &home)));
+    let muscle_map = LazyCell::new(|| muscles::MuscleMap::with_overrides(&config.muscles));
+    let exercise_aliases = LazyCell::new(|| aliases::Aliases::new(&config.aliases));
+    let log_unit = || config.unit.unwrap_or_default();
+    let mut out = pager::Pager::new(!args.no_pager);
 
-    match args.command {
+    match &args.command {
         Commands::List(list) => {
-            let client = ReqwestClient::new_with_verbose(args.verbose);
-            let token = match auth::login(&client, &args.credentials, &token_path).await {
-                Ok(t) => t,
-                Err(e) => {
-                    eprintln!("{}", e);
-                    std::process::exit(1);
-                }
-            };
-            let user = match client.get_user_info(&token).await {
-                Ok(u) => u,
-                Err(e) => {
-                    eprintln!("{}", e);
-                    std::process::exit(1);
-                }
-            };
+            // shared with the fetch tasks so days print as they arrive
+            let source: std::sync::Arc<dyn WorkoutSource> = open_source(&args, &token_path, log_unit).await.into();
+            let user = exit_on_error(source.user().await);
             let dates_to_use = if list.dates.is_empty() {
                 let (latest, oldest, count) = if list.all {
                     (None, None, 10000)
@@ -106,33 +404,20 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                     (None, None, 32)
                 };
 
-                match workouts::get_dates(&client, &token, latest, oldest, count, list.reverse).await {
-                    Ok(d) => d,
-                    Err(e) => {
-                        eprintln!("{}", e);
-                        std::process::exit(1);
-                    }
+                let mut dates = exit_on_error(source.dates(latest, oldest, count).await);
+                if list.reverse {
+                    dates.reverse();
                 }
+                dates
             } else {
                 // Parse ranges
                 let mut all_dates: Vec<String> = vec![];
                 for range_str in &list.dates {
                     let (oldest, latest) = match utils::parse_date_range(range_str) {
                         Ok(start_end) => start_end,
-                        Err(e) => {
-                            eprintln!("Invalid date range '{}': {}", range_str, e);
-                            std::process::exit(1);
-                        }
-                    };
-                    let count = ((oldest - latest).num_days().abs() + 1) as u32;
-                    let dates = match workouts::get_dates(&client, &token,
-                                        Some(latest.to_string()), Some(oldest.to_string()), count, false).await {
-                        Ok(d) => d,
-                        Err(e) => {
-                            eprintln!("{}", e);
-                            std::process::exit(1);
-                        }
+                        Err(e) => fail(format!("Invalid date range '{}': {}", range_str, e)),
                     };
+                    let dates = exit_on_error(source.dates_in_range(oldest, latest).await);
                     all_dates.extend(dates);
                 }
                 all_dates.sort();
@@ -143,19 +428,18 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             };
 
             if dates_to_use.is_empty() {
-                eprintln!("No workouts found in the specified range");
-                std::process::exit(1);
+                fail("No workouts found in the specified range");
             }
 
-            if list.details || list.summary {
+            let opts = formatters::FormatOptions { annotate: list.annotate, no_compress: list.no_compress, ..Default::default() };
+            if list.details || list.summary.is_some() {
                 let (tx, mut rx) = tokio::sync::mpsc::channel(32);
                 for (seq, date) in dates_to_use.iter().enumerate() {
                     let date = date.clone();
-                    let client_clone = client.clone();
-                    let token_clone = token.clone();
+                    let source_clone = source.clone();
                     let tx_clone = tx.clone();
                     tokio::spawn(async move {
-                        let result = match workouts::get_jday(&client_clone, &token_clone, &date).await {
+                        let result = match source_clone.workout(&date).await {
                             Ok(j) => Some(j),
                             Err(e) => {
                                 eprintln!("Error getting workout for {}: {}", date, e);
@@ -172,15 +456,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 while let Some((seq, date, result)) = rx.recv().await {
                     buffer.insert(seq, (date, result));
                     while let Some((d, r)) = buffer.remove(&next_seq) {
-                        if list.details {
-                            if let Some(jday) = r {
-                                let text = formatters::render_workout(&d, &jday, &user);
-                                println!("{}", text);
-                            }
-                        } else if list.summary {
-                            if let Some(j) = r {
-                                let summary = formatters::summarize_workout(&j);
-                                println!("{} {}", formatters::color_date(&d), summary);
+                        if let Some(j) = r {
+                            if list.details {
+                                out.println(&formatters::render_workout_with(&d, &j, &user, &opts));
+                            } else if let Some(mode) = list.summary {
+                                let summary = if list.by_pattern {
+                                    muscles::summarize_by_pattern(&j, mode, &muscle_map)
+                                } else {
+                                    formatters::summarize_workout_by(&j, mode)
+                                };
+                                out.println(&format!("{} {}", formatters::color_date(&d), summary));
                             }
                         }
                         next_seq += 1;
@@ -188,61 +473,336 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 }
             } else {
                 for date in dates_to_use {
-                    println!("{}", date);
+                    out.println(&date);
                 }
             }
         }
         Commands::Show(show) => {
-            let client = ReqwestClient::new_with_verbose(args.verbose);
-            let token = match auth::login(&client, &args.credentials, &token_path).await {
-                Ok(t) => t,
-                Err(e) => {
-                    eprintln!("{}", e);
-                    std::process::exit(1);
-                }
-            };
+            let source = open_source(&args, &token_path, log_unit).await;
 
-            let date = if let Some(d) = show.date {
-                d
+            let date = if let Some(d) = &show.date {
+                d.clone()
             } else {
                 // Show last workout
-                let dates = match workouts::get_dates(&client, &token, None, None, 1, false).await {
-                    Ok(d) => d,
-                    Err(e) => {
-                        eprintln!("{}", e);
-                        std::process::exit(1);
-                    }
-                };
-                if let Some(d) = dates.get(0) {
+                let dates = exit_on_error(source.dates(None, None, 1).await);
+                if let Some(d) = dates.first() {
                     d.clone()
                 } else {
-                    eprintln!("No workouts found");
-                    std::process::exit(1);
+                    fail("No workouts found");
                 }
             };
 
-            if show.summary {
-                let jday = match workouts::get_jday(&client, &token, &date).await {
-                    Ok(j) => j,
+            let jday = exit_on_error(source.workout(&date).await);
+            if let Some(mode) = show.summary {
+                let summary = if show.by_pattern {
+                    muscles::summarize_by_pattern(&jday, mode, &muscle_map)
+                } else {
+                    formatters::summarize_workout_by(&jday, mode)
+                };
+                out.println(&format!("{} {}", formatters::color_date(&date), summary));
+            } else {
+                let opts = formatters::FormatOptions { annotate: show.annotate, no_compress: show.no_compress, ..Default::default() };
+                let user = exit_on_error(source.user().await);
+                out.println(&formatters::render_workout_with(&date, &jday, &user, &opts));
+            }
+        }
+        Commands::Diff(diff_args) => {
+            let source = open_source(&args, &token_path, log_unit).await;
+            let date_a = parse_day(&diff_args.date_a);
+            let date_b = parse_day(&diff_args.date_b);
+            let (a, b) = tokio::join!(
+                source.workout(&date_a),
+                source.workout(&date_b)
+            );
+            let a = exit_on_error(a.map_err(|e| format!("{}: {}", date_a, e)));
+            let b = exit_on_error(b.map_err(|e| format!("{}: {}", date_b, e)));
+            let diffs = diff::diff_workouts(&a, &b, diff_args.exercise.as_deref());
+            out.println(&diff::render_diff(&date_a, &date_b, &diffs));
+        }
+        Commands::Stats(stats_args) => {
+            let source = open_source(&args, &token_path, log_unit).await;
+            let user = exit_on_error(source.user().await);
+            let days = fetch_range(source.as_ref(), Some(&stats_args.range)).await;
+            let days = if stats_args.no_group { days } else { aliases::group_days(&days, &exercise_aliases) };
+            let periods = stats::aggregate(&days, stats_args.by);
+            out.println(&stats::render_stats(&periods, stats_args.by, user.usekg.unwrap_or(1) != 1));
+        }
+        Commands::Prs(prs_args) => {
+            let source = open_source(&args, &token_path, log_unit).await;
+            let user = exit_on_error(source.user().await);
+            let days = fetch_range(source.as_ref(), prs_args.range.as_deref()).await;
+            let group = (!prs_args.no_group).then_some(&*exercise_aliases);
+            let all = records::build_records(&days, prs_args.exercise.as_deref(), group);
+            if all.is_empty() {
+                fail("No weighted sets found");
+            }
+            out.println(&records::render_records(&all, user.usekg.unwrap_or(1) != 1));
+        }
+        Commands::Trend(trend_args) => {
+            let source = open_source(&args, &token_path, log_unit).await;
+            let user = exit_on_error(source.user().await);
+            let days = fetch_range(source.as_ref(), Some(&trend_args.range)).await;
+            let group = (!trend_args.no_group).then_some(&*exercise_aliases);
+            let table = exit_on_error(rpe::RpeTable::with_overrides(&config.rpe_table));
+            let bests = trend::session_bests(&days, &trend_args.exercise, trend_args.formula, &table, group);
+            if bests.is_empty() {
+                fail(format!("No sets of '{}' found in the specified range", trend_args.exercise));
+            }
+            out.println(&trend::render_trend(&bests, user.usekg.unwrap_or(1) != 1));
+        }
+        Commands::Bw(bw_args) => {
+            let source = open_source(&args, &token_path, log_unit).await;
+            let user = exit_on_error(source.user().await);
+            let (oldest, latest) = match utils::parse_date_range(&bw_args.range) {
+                Ok(start_end) => start_end,
+                Err(e) => fail(format!("Invalid date range '{}': {}", bw_args.range, e)),
+            };
+            let weights: Vec<(chrono::NaiveDate, f32)> = exit_on_error(source.bodyweights(oldest, latest).await)
+                .into_iter()
+                .filter_map(|(date, bw)| Some((date.parse().ok()?, formatters::user_bw(bw, &user))))
+                .collect();
+            if weights.is_empty() {
+                fail("No bodyweight logged in the specified range");
+            }
+            out.println(&bodyweight::render_bodyweight(&weights));
+        }
+        Commands::Calendar(calendar_args) => {
+            let source = open_source(&args, &token_path, log_unit).await;
+            let year = calendar_args.year.unwrap_or_else(|| chrono::Local::now().year());
+            let (Some(oldest), Some(latest)) = (chrono::NaiveDate::from_ymd_opt(year, 1, 1), chrono::NaiveDate::from_ymd_opt(year, 12, 31)) else {
+                fail(format!("Invalid year '{}'", year));
+            };
+            let dates = exit_on_error(source.dates_in_range(oldest, latest).await);
+            let mut values = std::collections::BTreeMap::new();
+            if calendar_args.metric == calendar::Metric::Presence {
+                values.extend(dates.iter().filter_map(|d| d.parse().ok()).map(|d| (d, 1.0)));
+            } else {
+                for (date, result) in source.workouts(&dates).await {
+                    match result {
+                        Ok(jday) => if let Ok(day) = date.parse() {
+                            values.insert(day, calendar::day_value(&jday, calendar_args.metric));
+                        },
+                        Err(e) => eprintln!("Error getting workout for {}: {}", date, e),
+                    }
+                }
+            }
+            out.println(&calendar::render_calendar(year, &values));
+        }
+        Commands::Streaks(streaks_args) => {
+            let source = open_source(&args, &token_path, log_unit).await;
+            let dates: Vec<chrono::NaiveDate> = exit_on_error(source.dates(None, None, 10000).await)
+                .iter()
+                .filter_map(|d| d.parse().ok())
+                .collect();
+            if dates.is_empty() {
+                fail("No workouts found");
+            }
+            let target = streaks_args.target.or(config.target_sessions_per_week);
+            let report = streaks::build_report(&dates, chrono::Local::now().date_naive(), target);
+            out.println(&streaks::render_report(&report));
+        }
+        Commands::Exercise(exercise_args) => {
+            let source = open_source(&args, &token_path, log_unit).await;
+            let days = fetch_range(source.as_ref(), exercise_args.range.as_deref()).await;
+            let group = (!exercise_args.no_group).then_some(&*exercise_aliases);
+            let entries = history::exercise_history_with(&days, &exercise_args.exercise, group);
+            if entries.is_empty() {
+                fail(format!("No sets of '{}' found in the specified range", exercise_args.exercise));
+            }
+            out.println(&history::render_history(&entries));
+        }
+        Commands::Search(search_args) => {
+            let re = match regex::RegexBuilder::new(&search_args.pattern).case_insensitive(search_args.ignore_case).build() {
+                Ok(re) => re,
+                Err(e) => fail(format!("Invalid pattern '{}': {}", search_args.pattern, e)),
+            };
+            let source = open_source(&args, &token_path, log_unit).await;
+            let days = fetch_range(source.as_ref(), search_args.range.as_deref()).await;
+            let hits = search::search(&days, &re, search_args.context);
+            if hits.is_empty() {
+                fail(format!("No matches for '{}'", search_args.pattern));
+            }
+            out.println(&search::render_hits(&hits));
+        }
+        Commands::Load(load_args) => {
+            let (oldest, latest) = match utils::parse_date_range(&load_args.range) {
+                Ok(start_end) => start_end,
+                Err(e) => fail(format!("Invalid date range '{}': {}", load_args.range, e)),
+            };
+            let source = open_source(&args, &token_path, log_unit).await;
+            let user = exit_on_error(source.user().await);
+            // the chronic window needs the weeks before the range too
+            let history_start = oldest - chrono::Duration::days(load::CHRONIC_DAYS - 1);
+            let history = format!("{}..{}", history_start, latest);
+            let days = fetch_range(source.as_ref(), Some(&history)).await;
+            let loads = load::daily_loads(&days, load_args.by, history_start, latest);
+            let rows = load::workload(&loads, oldest);
+            out.println(&load::render_load(&rows, load_args.by, user.usekg.unwrap_or(1) != 1));
+        }
+        Commands::Rpe(rpe_args) => {
+            let table = exit_on_error(rpe::RpeTable::with_overrides(&config.rpe_table));
+            if rpe_args.table {
+                out.println(&table.render());
+            } else {
+                let source = open_source(&args, &token_path, log_unit).await;
+                let user = exit_on_error(source.user().await);
+                let days = fetch_range(source.as_ref(), rpe_args.range.as_deref()).await;
+                let weeks = rpe::weekly_rpe(&days, rpe_args.exercise.as_deref(), &table);
+                if weeks.is_empty() {
+                    fail("No sets with RPE found in the specified range");
+                }
+                let drifts = rpe::rpe_drift(&days, rpe_args.exercise.as_deref());
+                out.println(&rpe::render_analysis(&weeks, &drifts, user.usekg.unwrap_or(1) != 1));
+            }
+        }
+        Commands::Report(report_args) => {
+            let period = if report_args.month {
+                report::Period::Month
+            } else if report_args.year {
+                report::Period::Year
+            } else {
+                report::Period::Week
+            };
+            let date = match &report_args.date {
+                Some(d) => exit_on_error(utils::parse_date_boundary(d, false).map_err(|e| format!("Invalid date '{}': {}", d, e))),
+                None => chrono::Local::now().date_naive(),
+            };
+            let (oldest, latest) = exit_on_error(report::period_bounds(period, date));
+            let (prev_oldest, prev_latest) = exit_on_error(report::previous_bounds(period, oldest));
+            let source = open_source(&args, &token_path, log_unit).await;
+            let user = exit_on_error(source.user().await);
+            let days = fetch_period(source.as_ref(), oldest, latest).await;
+            // PRs are judged against everything logged before the period,
+            // which holds the previous period too
+            let before = (oldest - chrono::Duration::days(1)).to_string();
+            let earlier_dates = exit_on_error(source.dates(Some(before), None, 0).await);
+            let earlier = fetch_days(source.as_ref(), &earlier_dates).await;
+            let (prev_oldest, prev_latest) = (prev_oldest.to_string(), prev_latest.to_string());
+            let previous_days: Vec<_> = earlier.iter()
+                .filter(|(d, _)| *d >= prev_oldest && *d <= prev_latest)
+                .cloned()
+                .collect();
+            let report = report::build_report(period, oldest, latest, &days, &previous_days, &earlier);
+            let lb = user.usekg.unwrap_or(1) != 1;
+            let user_bw = |bw: f32| formatters::user_bw(bw, &user);
+            if report_args.markdown {
+                out.println(&report::render_markdown(&report, lb, user_bw));
+            } else {
+                out.println(&report::render_text(&report, lb, user_bw));
+            }
+        }
+        Commands::Volume(volume_args) => {
+            let source = open_source(&args, &token_path, log_unit).await;
+            let days = fetch_range(source.as_ref(), Some(&volume_args.range)).await;
+            let weeks = muscles::weekly_volume(&days, volume_args.by, &muscle_map);
+            out.println(&muscles::render_volume(&weeks, volume_args.by));
+        }
+        Commands::Compare(compare_args) => {
+            let mut periods = Vec::new();
+            for range in [&compare_args.range_a, &compare_args.range_b] {
+                let (oldest, latest) = match utils::parse_date_range(range) {
+                    Ok(start_end) => start_end,
+                    Err(e) => fail(format!("Invalid date range '{}': {}", range, e)),
+                };
+                periods.push((oldest, latest));
+            }
+            let source = open_source(&args, &token_path, log_unit).await;
+            let user = exit_on_error(source.user().await);
+            let mut summaries = Vec::new();
+            for (oldest, latest) in periods {
+                let days = fetch_period(source.as_ref(), oldest, latest).await;
+                let days = if compare_args.no_group { days } else { aliases::group_days(&days, &exercise_aliases) };
+                summaries.push(compare::summarize_period(&days));
+            }
+            out.println(&compare::render_compare(
+                &compare_args.range_a,
+                &compare_args.range_b,
+                &summaries[0],
+                &summaries[1],
+                user.usekg.unwrap_or(1) != 1,
+                |bw| formatters::user_bw(bw, &user),
+            ));
+        }
+        Commands::Zones(zones_args) => {
+            let source = open_source(&args, &token_path, log_unit).await;
+            let user = exit_on_error(source.user().await);
+            let lb = user.usekg.unwrap_or(1) != 1;
+            let days = fetch_range(source.as_ref(), Some(&zones_args.range)).await;
+            let exercise = &zones_args.exercise;
+            let (reference, max) = match zones_args.reference {
+                zones::Reference::E1rm => match zones::best_e1rm(&days, exercise) {
+                    Some((e1rm, date)) => (zones::describe_e1rm(e1rm, &date, lb), e1rm),
+                    None => fail(format!("No sets of '{}' to estimate a 1RM from in the specified range", exercise)),
+                },
+                zones::Reference::TrainingMax => match config.training_max(exercise) {
+                    // the config holds it in the user's unit, sets are in kg
+                    Some(tm) => (format!("training max {}", formatters::format_number(tm)), if lb { tm / formatters::LB_PER_KG } else { tm }),
+                    None => fail(format!("No training max for '{}' in the config file", exercise)),
+                },
+                zones::Reference::Server => ("server intensity".to_string(), 0.0),
+            };
+            let percent = |set: &models::Set| match zones_args.reference {
+                zones::Reference::Server => set.intensity_pct(),
+                _ => set.w.map(|w| w / max * 100.0),
+            };
+            let sessions = zones::session_zones(&days, exercise, percent);
+            if sessions.is_empty() {
+                fail(format!("No working sets of '{}' found in the specified range", exercise));
+            }
+            out.println(&zones::render_zones(&sessions, &reference));
+        }
+        Commands::Parse(parse_args) => {
+            let unit = parse_args.unit.unwrap_or_else(log_unit);
+            let text = exit_on_error(std::fs::read_to_string(&parse_args.file)
+                .map_err(|e| format!("Failed to read {}: {}", parse_args.file, e)));
+            let workout = match parser::parse(&text, unit) {
+                Ok(w) => w,
+                Err(e) => fail(e.render(&parse_args.file, &text)),
+            };
+            // echo the workout back the way `show` prints it
+            let user = models::User { usekg: Some(if unit == parser::Unit::Lb { 0 } else { 1 }) };
+            let date = workout.date.as_deref().unwrap_or(&parse_args.file);
+            out.println(&formatters::render_workout_with(date, &workout.jday, &user, &formatters::FormatOptions::default()));
+        }
+        Commands::Fmt(fmt_args) => {
+            // like rustfmt: rewrite in place, or with --check list what would
+            // change and fail; files that do not parse fail either way
+            let mut failed = false;
+            for file in &fmt_args.files {
+                let text = match std::fs::read_to_string(file) {
+                    Ok(text) => text,
                     Err(e) => {
-                        eprintln!("{}", e);
-                        std::process::exit(1);
+                        eprintln!("Failed to read {}: {}", file, e);
+                        failed = true;
+                        continue;
                     }
                 };
-                let summary = formatters::summarize_workout(&jday);
-                println!("{} {}", formatters::color_date(&date), summary);
-            } else {
-                let workout = match workouts::get_day(&client, &token, &date).await {
-                    Ok(w) => w,
+                let formatted = match logfmt::format_log(&text, fmt_args.unit.unwrap_or_else(log_unit)) {
+                    Ok(formatted) => formatted,
                     Err(e) => {
-                        eprintln!("{}", e);
-                        std::process::exit(1);
+                        eprintln!("{}", e.render(file, &text));
+                        failed = true;
+                        continue;
                     }
                 };
-                println!("{}", workout);
+                if fmt_args.check {
+                    if let Some(diff) = logfmt::render_check(file, &text, &formatted) {
+                        out.println(&diff);
+                        failed = true;
+                    }
+                } else if formatted != text
+                    && let Err(e) = std::fs::write(file, &formatted) {
+                    eprintln!("Failed to write {}: {}", file, e);
+                    failed = true;
+                }
+            }
+            if failed {
+                out.finish();
+                std::process::exit(1);
             }
         }
     }
 
+    out.finish();
     Ok(())
 }
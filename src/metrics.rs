@@ -0,0 +1,161 @@
+use crate::models::{Exercise, Set};
+use crate::rpe::RpeTable;
+
+// Epley estimate; a single is its own 1RM
+pub fn epley(w: f32, r: u32) -> f32 {
+    if r <= 1 {
+        w
+    } else {
+        w * (1.0 + r as f32 / 30.0)
+    }
+}
+
+pub fn brzycki(w: f32, r: u32) -> f32 {
+    match r {
+        0 | 1 => w,
+        // the formula blows up at 37 reps
+        37.. => epley(w, r),
+        _ => w * 36.0 / (37.0 - r as f32),
+    }
+}
+
+pub fn wathan(w: f32, r: u32) -> f32 {
+    if r <= 1 {
+        return w;
+    }
+    100.0 * w / (48.8 + 53.8 * (-0.075 * r as f32).exp())
+}
+
+pub fn lombardi(w: f32, r: u32) -> f32 {
+    w * (r.max(1) as f32).powf(0.10)
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Formula {
+    // the server's est1rm when present, Epley otherwise
+    #[default]
+    Server,
+    Epley,
+    Brzycki,
+    Wathan,
+    Lombardi,
+    Rpe,
+}
+
+// e1RM of a plain weight x reps set with the given formula, 0 for anything
+// else; the RPE formula reads `table` and skips sets logged without an RPE
+pub fn estimate_1rm(set: &Set, formula: Formula, table: &RpeTable) -> f32 {
+    let w = set.w.unwrap_or(0.0);
+    let r = set.r.unwrap_or(0);
+    if !set.is_weight_reps() || set.is_bodyweight() || w <= 0.0 || r == 0 {
+        return 0.0;
+    }
+    match formula {
+        Formula::Server => set_e1rm(set),
+        Formula::Epley => epley(w, r),
+        Formula::Brzycki => brzycki(w, r),
+        Formula::Wathan => wathan(w, r),
+        Formula::Lombardi => lombardi(w, r),
+        Formula::Rpe => table.e1rm(set).unwrap_or(0.0),
+    }
+}
+
+// prefer the server's estimate, fall back to Epley for plain weight x reps sets
+pub fn set_e1rm(set: &Set) -> f32 {
+    if let Some(e) = set.est1rm
+        && e > 0.0 {
+        return e;
+    }
+    let w = set.w.unwrap_or(0.0);
+    let r = set.r.unwrap_or(0);
+    if set.is_weight_reps() && !set.is_bodyweight() && w > 0.0 && r > 0 {
+        epley(w, r)
+    } else {
+        0.0
+    }
+}
+
+// tonnage: w x r x s
+pub fn set_volume(set: &Set) -> f32 {
+    set.w.unwrap_or(0.0) * set.r.unwrap_or(0) as f32 * set.s.unwrap_or(1) as f32
+}
+
+pub fn set_reps(set: &Set) -> u32 {
+    set.r.unwrap_or(0) * set.s.unwrap_or(1)
+}
+
+// heaviest set, then most reps
+pub fn top_set<'a>(sets: impl IntoIterator<Item = &'a Set>) -> Option<&'a Set> {
+    sets.into_iter().max_by(|x, y| {
+        x.w.unwrap_or(0.0).total_cmp(&y.w.unwrap_or(0.0))
+            .then(x.r.unwrap_or(0).cmp(&y.r.unwrap_or(0)))
+    })
+}
+
+pub fn best_e1rm(sets: &[Set]) -> f32 {
+    sets.iter().map(set_e1rm).fold(0.0, f32::max)
+}
+
+pub fn total_volume(sets: &[Set]) -> f32 {
+    sets.iter().map(set_volume).sum()
+}
+
+pub fn total_reps(sets: &[Set]) -> u32 {
+    sets.iter().map(set_reps).sum()
+}
+
+// a set is a working set when it is at least 60% of the heaviest weight in
+// the block; blocks without any weight (bodyweight, timed) count every set
+pub fn working_sets(sets: &[Set]) -> u32 {
+    working(sets).map(|s| s.s.unwrap_or(1)).sum()
+}
+
+// the sets working_sets counts
+pub fn working(sets: &[Set]) -> impl Iterator<Item = &Set> {
+    let top = sets.iter().map(|s| s.w.unwrap_or(0.0)).fold(0.0, f32::max);
+    sets.iter().filter(move |s| top <= 0.0 || s.w.unwrap_or(0.0) >= 0.6 * top)
+}
+
+// working sets weighted by their RPE; sets logged without one count as RPE 10
+pub fn rpe_load(sets: &[Set]) -> f32 {
+    working(sets)
+        .map(|s| s.s.unwrap_or(1) as f32 * s.rpe.filter(|&r| r > 0.0).unwrap_or(10.0))
+        .sum()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockKind {
+    Weighted,
+    // pull-ups, push-ups, dips; possibly with added weight
+    Bodyweight,
+    Timed,
+    Distance,
+}
+
+// The exercise type decides when the server sends one (anything that is not
+// bodyweight, distance or timed work is weighted); only without it do we go
+// by what was logged.
+pub fn classify(ex: &Exercise, sets: &[Set]) -> BlockKind {
+    if let Some(t) = ex.ex_type.as_deref().map(str::trim).filter(|t| !t.is_empty()) {
+        let t = t.to_lowercase();
+        return if t.contains("bodyweight") || t == "bw" || t.contains("calisthenic") {
+            BlockKind::Bodyweight
+        } else if t.contains("distance") || t.contains("cardio") {
+            BlockKind::Distance
+        } else if t.contains("time") || t.contains("duration") || t.contains("isometric") {
+            BlockKind::Timed
+        } else {
+            BlockKind::Weighted
+        };
+    }
+    if sets.iter().any(|s| s.has_distance()) {
+        BlockKind::Distance
+    } else if sets.iter().any(|s| s.has_time()) {
+        BlockKind::Timed
+    } else if sets.iter().any(|s| s.is_bodyweight())
+        || (!sets.is_empty() && sets.iter().all(|s| s.w.unwrap_or(0.0) == 0.0 && s.r.unwrap_or(0) > 0)) {
+        BlockKind::Bodyweight
+    } else {
+        BlockKind::Weighted
+    }
+}
@@ -43,6 +43,22 @@ pub struct JRangeDayData {
     pub on: Option<String>,
 }
 
+#[derive(Deserialize, Debug)]
+pub struct GetJRangeBwData {
+    pub jrange: Option<JRangeBwData>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct JRangeBwData {
+    pub days: Option<Vec<JRangeBwDayData>>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct JRangeBwDayData {
+    pub on: Option<String>,
+    pub bw: Option<f32>,
+}
+
 #[derive(Serialize)]
 pub struct WorkoutRequest {
     pub query: String,
@@ -56,7 +72,6 @@ pub struct WorkoutVariables {
 }
 
 #[derive(Deserialize)]
-#[allow(dead_code)]
 pub struct WorkoutResponse {
     pub data: Option<WorkoutData>,
     pub errors: Option<Vec<GraphQLError>>,
@@ -68,7 +83,6 @@ pub struct WorkoutData {
 }
 
 #[derive(Deserialize, Debug, Clone)]
-#[allow(dead_code)]
 pub struct JDay {
     pub log: String,
     pub bw: Option<f32>,
@@ -77,19 +91,18 @@ pub struct JDay {
 }
 
 #[derive(Deserialize, Debug, Clone)]
-#[allow(dead_code)]
 pub struct EBlock {
     pub eid: String,
     pub sets: Vec<Set>,
 }
 
 #[derive(Deserialize, Debug, Default, Clone)]
-#[allow(dead_code)]
 pub struct Set {
     pub w: Option<f32>,
     pub r: Option<u32>,
     pub s: Option<u32>,
     pub lb: Option<f32>,
+    pub ubw: Option<i32>,
     pub rpe: Option<f32>,
     pub pr: Option<i32>,
     pub est1rm: Option<f32>,
@@ -106,13 +119,11 @@ pub struct Set {
 }
 
 #[derive(Deserialize, Debug, Clone)]
-#[allow(dead_code)]
 pub struct ExerciseWrapper {
     pub exercise: Exercise,
 }
 
 #[derive(Deserialize, Debug, Clone)]
-#[allow(dead_code)]
 pub struct Exercise {
     pub id: String,
     pub name: String,
@@ -134,4 +145,98 @@ pub struct UserBasicInfoData {
 #[derive(Deserialize)]
 pub struct SessionInfo {
     pub user: User,
-}
\ No newline at end of file
+}
+impl JDay {
+    pub fn exercise(&self, eid: &str) -> Option<&Exercise> {
+        self.exercises.iter().map(|w| &w.exercise).find(|e| e.id == eid)
+    }
+
+    // hashtags given to a block, from the exercise name ("safety-squat #sq")
+    // and from the log line holding the block; the free text around it is
+    // notes ("#deload"), not tags
+    pub fn tags(&self, eblock: &EBlock) -> Vec<String> {
+        let mut tags: Vec<String> = Vec::new();
+        if let Some(ex) = self.exercise(&eblock.eid) {
+            tags.extend(hashtags(&ex.name));
+        }
+        let placeholder = format!("EBLOCK:{}", eblock.eid);
+        if let Some(line) = self.log.lines().find(|l| l.split_whitespace().any(|w| w == placeholder)) {
+            tags.extend(hashtags(line));
+        }
+        tags.dedup();
+        tags
+    }
+
+    // the block's exercise matches, or the query is one of its tags
+    pub fn eblock_matches(&self, eblock: &EBlock, query: &str) -> bool {
+        let tag = query.trim_start_matches('#');
+        self.exercise(&eblock.eid).is_some_and(|ex| ex.matches(query))
+            || self.tags(eblock).iter().any(|t| t.eq_ignore_ascii_case(tag))
+    }
+}
+
+// "#Safety Squat" -> "safety-squat", for comparing names and tags
+pub fn normalize_name(name: &str) -> String {
+    name.trim().trim_start_matches('#').to_lowercase().replace([' ', '_'], "-")
+}
+
+// "#bench-press #bp" -> ["bench-press", "bp"]
+fn hashtags(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .filter_map(|w| w.strip_prefix('#'))
+        .filter(|t| !t.is_empty())
+        .map(|t| t.to_string())
+        .collect()
+}
+
+impl Exercise {
+    // by id or by name, ignoring case and the leading '#' used in logs; a
+    // name carrying tags ("safety-squat #sq") also matches without them
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.trim_start_matches('#');
+        let base = self.name.split(" #").next().unwrap_or("").trim();
+        self.id == query || self.name.eq_ignore_ascii_case(query) || base.eq_ignore_ascii_case(query)
+    }
+}
+
+impl Set {
+    // `t` comes from the server in milliseconds
+    pub fn has_time(&self) -> bool {
+        self.t.unwrap_or(0.0) > 0.0
+    }
+
+    pub fn has_distance(&self) -> bool {
+        self.d.unwrap_or(0.0) > 0.0
+    }
+
+    // plain weight x reps sets, the only kind compress_sets groups together
+    pub fn is_weight_reps(&self) -> bool {
+        self.set_type.unwrap_or(0) == 0 && !self.has_time() && !self.has_distance()
+    }
+
+    // sets marked as bodyweight ("BW+25" in a log) carry only the added (or
+    // assisting, if negative) load in `w`
+    pub fn is_bodyweight(&self) -> bool {
+        self.ubw.unwrap_or(0) == 1
+    }
+
+    // `int` is sent as a fraction of the lifter's best e1RM
+    pub fn intensity_pct(&self) -> Option<f32> {
+        self.int.filter(|&i| i > 0.0).map(|i| i * 100.0)
+    }
+
+    // distance normalized to meters, so sets logged in different units compare
+    pub fn distance_m(&self) -> f32 {
+        let d = self.d.unwrap_or(0.0);
+        let factor = match self.dunit.as_deref().unwrap_or("m").to_lowercase().as_str() {
+            "km" => 1000.0,
+            "mi" | "mile" | "miles" => 1609.344,
+            "yd" | "yds" => 0.9144,
+            "ft" => 0.3048,
+            "cm" => 0.01,
+            "in" => 0.0254,
+            _ => 1.0,
+        };
+        d * factor
+    }
+}
@@ -0,0 +1,161 @@
+use std::collections::BTreeMap;
+use chrono::NaiveDate;
+use serde::Deserialize;
+
+use crate::formatters;
+use crate::metrics;
+use crate::models::{normalize_name, EBlock, JDay};
+use crate::stats::{self, GroupBy};
+
+// what an exercise trains
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Mapping {
+    pub pattern: String,
+    #[serde(default)]
+    pub muscles: Vec<String>,
+}
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VolumeBy {
+    #[default]
+    Muscle,
+    Pattern,
+}
+
+// blocks nothing maps to are counted under this name
+pub const UNMAPPED: &str = "unmapped";
+
+// key (exercise name, tag or name word), pattern, muscles
+const DEFAULT_MAPPING: &[(&str, &str, &[&str])] = &[
+    ("squat", "squat", &["quads", "glutes", "adductors"]),
+    ("sq", "squat", &["quads", "glutes", "adductors"]),
+    ("leg-press", "squat", &["quads", "glutes"]),
+    ("lunge", "lunge", &["quads", "glutes"]),
+    ("deadlift", "hinge", &["hamstrings", "glutes", "lower back"]),
+    ("dl", "hinge", &["hamstrings", "glutes", "lower back"]),
+    ("rdl", "hinge", &["hamstrings", "glutes"]),
+    ("good-morning", "hinge", &["hamstrings", "lower back"]),
+    ("hip-thrust", "hinge", &["glutes"]),
+    ("bench", "horizontal push", &["chest", "triceps", "front delts"]),
+    ("bench-press", "horizontal push", &["chest", "triceps", "front delts"]),
+    ("bp", "horizontal push", &["chest", "triceps", "front delts"]),
+    ("push-up", "horizontal push", &["chest", "triceps", "front delts"]),
+    ("dip", "vertical push", &["chest", "triceps"]),
+    ("press", "vertical push", &["shoulders", "triceps"]),
+    ("ohp", "vertical push", &["shoulders", "triceps"]),
+    ("overhead-press", "vertical push", &["shoulders", "triceps"]),
+    ("row", "horizontal pull", &["upper back", "lats", "biceps"]),
+    ("pull-up", "vertical pull", &["lats", "biceps"]),
+    ("pullup", "vertical pull", &["lats", "biceps"]),
+    ("chin-up", "vertical pull", &["lats", "biceps"]),
+    ("chinup", "vertical pull", &["lats", "biceps"]),
+    ("pulldown", "vertical pull", &["lats", "biceps"]),
+    ("curl", "isolation", &["biceps"]),
+    ("pushdown", "isolation", &["triceps"]),
+    ("lateral-raise", "isolation", &["shoulders"]),
+    ("calf-raise", "isolation", &["calves"]),
+    ("plank", "core", &["abs"]),
+    ("crunch", "core", &["abs"]),
+    ("carry", "carry", &["grip", "traps", "abs"]),
+];
+
+// Exercise names and tags to patterns and muscles. The built-in table covers
+// the common lifts; the config file can replace entries or add new ones.
+#[derive(Debug, Clone)]
+pub struct MuscleMap {
+    entries: BTreeMap<String, Mapping>,
+}
+
+impl Default for MuscleMap {
+    fn default() -> Self {
+        let entries = DEFAULT_MAPPING.iter()
+            .map(|(key, pattern, muscles)| (key.to_string(), Mapping {
+                pattern: pattern.to_string(),
+                muscles: muscles.iter().map(|m| m.to_string()).collect(),
+            }))
+            .collect();
+        MuscleMap { entries }
+    }
+}
+
+impl MuscleMap {
+    pub fn with_overrides(overrides: &BTreeMap<String, Mapping>) -> Self {
+        let mut map = MuscleMap::default();
+        for (key, mapping) in overrides {
+            map.entries.insert(normalize_name(key), mapping.clone());
+        }
+        map
+    }
+
+    // the full name first, then the name without its tags, then the tags,
+    // then runs of words in the name, longest first and from the end, so
+    // "incline-bench-press" finds "bench-press" before "press"
+    pub fn lookup(&self, jday: &JDay, eblock: &EBlock) -> Option<&Mapping> {
+        let name = jday.exercise(&eblock.eid).map(|ex| ex.name.as_str()).unwrap_or("");
+        let base = name.split(" #").next().unwrap_or("");
+        let mut candidates = vec![normalize_name(name), normalize_name(base)];
+        candidates.extend(jday.tags(eblock).iter().map(|t| normalize_name(t)));
+        let base = normalize_name(base);
+        let words: Vec<&str> = base.split('-').collect();
+        for len in (1..words.len()).rev() {
+            candidates.extend((0..=words.len() - len).rev().map(|start| words[start..start + len].join("-")));
+        }
+        candidates.iter().find_map(|c| self.entries.get(c))
+    }
+}
+
+pub struct WeekVolume {
+    pub week: String,
+    // hard sets per muscle or pattern
+    pub sets: BTreeMap<String, u32>,
+}
+
+// Every muscle an exercise maps to gets the block's full working-set count.
+// `days` are (YYYY-MM-DD, workout) pairs, oldest first.
+pub fn weekly_volume(days: &[(String, JDay)], by: VolumeBy, map: &MuscleMap) -> Vec<WeekVolume> {
+    let mut weeks: Vec<WeekVolume> = Vec::new();
+    for (date, jday) in days {
+        let Ok(day) = date.parse::<NaiveDate>() else {
+            continue;
+        };
+        let week = stats::period_label(day, GroupBy::Week);
+        if weeks.last().is_none_or(|w| w.week != week) {
+            weeks.push(WeekVolume { week, sets: BTreeMap::new() });
+        }
+        let current = weeks.last_mut().unwrap();
+        for eblock in &jday.eblocks {
+            let hard_sets = metrics::working_sets(&eblock.sets);
+            let groups = match (map.lookup(jday, eblock), by) {
+                (Some(m), VolumeBy::Muscle) if !m.muscles.is_empty() => m.muscles.clone(),
+                (Some(m), VolumeBy::Pattern) => vec![m.pattern.clone()],
+                _ => vec![UNMAPPED.to_string()],
+            };
+            for group in groups {
+                *current.sets.entry(group).or_default() += hard_sets;
+            }
+        }
+    }
+    weeks
+}
+
+pub fn render_volume(weeks: &[WeekVolume], by: VolumeBy) -> String {
+    let label = if by == VolumeBy::Muscle { "muscle" } else { "pattern" };
+    let mut rows = Vec::new();
+    for week in weeks {
+        for (i, (group, sets)) in week.sets.iter().enumerate() {
+            rows.push(vec![
+                if i == 0 { formatters::color_date(&week.week) } else { String::new() },
+                group.clone(),
+                sets.to_string(),
+            ]);
+        }
+    }
+    formatters::render_table(&["week", label, "hard sets"], &rows)
+}
+
+// `--summary` with exercises gathered under their movement pattern
+pub fn summarize_by_pattern(jday: &JDay, mode: formatters::SummaryMode, map: &MuscleMap) -> String {
+    formatters::summarize_workout_grouped(jday, mode, |eblock| {
+        map.lookup(jday, eblock).map(|m| m.pattern.clone()).unwrap_or(UNMAPPED.to_string())
+    })
+}
@@ -0,0 +1,164 @@
+use std::io::{self, Write};
+use std::process::{Child, Command, Stdio};
+use std::sync::Mutex;
+
+// Output goes to the terminal until it no longer fits on one screen, at which
+// point everything is handed over to $PAGER (default `less -R`).
+enum Sink {
+    // stdout is a tty; hold on to lines until we know whether they fit
+    Buffer(Vec<String>),
+    Pager(Child),
+    Stdout,
+}
+
+struct State {
+    sink: Sink,
+    height: usize,
+    lines: usize,
+}
+
+// the state of the one pager a run writes to, so `flush` works from anywhere
+static ACTIVE: Mutex<Option<State>> = Mutex::new(None);
+
+pub struct Pager {
+    _active: (),
+}
+
+impl Pager {
+    pub fn new(enabled: bool) -> Self {
+        let sink = if enabled && atty::is(atty::Stream::Stdout) {
+            Sink::Buffer(Vec::new())
+        } else {
+            Sink::Stdout
+        };
+        *active() = Some(State {
+            sink,
+            height: terminal_height(),
+            lines: 0,
+        });
+        Pager { _active: () }
+    }
+
+    pub fn println(&mut self, text: &str) {
+        if let Some(state) = active().as_mut() {
+            state.println(text);
+        }
+    }
+
+    pub fn finish(self) {
+        finish_active();
+    }
+}
+
+// Write out (or wait on the pager for) everything printed so far; call before
+// std::process::exit, which would drop buffered lines
+pub fn flush() {
+    finish_active();
+}
+
+fn active() -> std::sync::MutexGuard<'static, Option<State>> {
+    ACTIVE.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn finish_active() {
+    let state = active().take();
+    if let Some(state) = state {
+        state.finish();
+    }
+}
+
+impl State {
+    fn println(&mut self, text: &str) {
+        let result = match &mut self.sink {
+            Sink::Buffer(buffer) => {
+                self.lines += text.lines().count().max(1);
+                buffer.push(text.to_string());
+                if self.lines >= self.height {
+                    self.start_pager();
+                }
+                Ok(())
+            }
+            Sink::Pager(child) => match child.stdin.as_mut() {
+                Some(stdin) => writeln!(stdin, "{}", text),
+                None => Ok(()),
+            },
+            Sink::Stdout => writeln!(io::stdout().lock(), "{}", text),
+        };
+        if let Err(e) = result {
+            self.closed(e);
+        }
+    }
+
+    fn finish(mut self) {
+        match std::mem::replace(&mut self.sink, Sink::Stdout) {
+            Sink::Buffer(buffer) => {
+                for text in buffer {
+                    self.println(&text);
+                }
+                if let Err(e) = io::stdout().flush() {
+                    self.closed(e);
+                }
+            }
+            Sink::Pager(mut child) => {
+                drop(child.stdin.take());
+                let _ = child.wait();
+            }
+            Sink::Stdout => {
+                if let Err(e) = io::stdout().flush() {
+                    self.closed(e);
+                }
+            }
+        }
+    }
+
+    fn start_pager(&mut self) {
+        let buffer = match std::mem::replace(&mut self.sink, Sink::Stdout) {
+            Sink::Buffer(buffer) => buffer,
+            other => {
+                self.sink = other;
+                return;
+            }
+        };
+        if let Some(child) = spawn_pager() {
+            self.sink = Sink::Pager(child);
+        }
+        for text in buffer {
+            self.println(&text);
+        }
+    }
+
+    // the reader went away (`| head`, or quitting the pager): stop quietly
+    fn closed(&mut self, e: io::Error) -> ! {
+        if let Sink::Pager(child) = &mut self.sink {
+            drop(child.stdin.take());
+            let _ = child.wait();
+        }
+        if e.kind() == io::ErrorKind::BrokenPipe {
+            std::process::exit(0);
+        }
+        eprintln!("Error writing output: {}", e);
+        std::process::exit(1);
+    }
+}
+
+fn spawn_pager() -> Option<Child> {
+    let pager = std::env::var("PAGER").unwrap_or("less -R".to_string());
+    let mut parts = pager.split_whitespace();
+    let program = parts.next()?;
+    if program == "cat" {
+        return None;
+    }
+    Command::new(program)
+        .args(parts)
+        .stdin(Stdio::piped())
+        .spawn()
+        .ok()
+}
+
+fn terminal_height() -> usize {
+    if let Some((_, terminal_size::Height(rows))) = terminal_size::terminal_size()
+        && rows > 0 {
+        return rows as usize;
+    }
+    std::env::var("LINES").ok().and_then(|l| l.parse().ok()).unwrap_or(24)
+}
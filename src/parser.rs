@@ -0,0 +1,644 @@
+use std::fmt;
+use chrono::NaiveDate;
+use serde::Deserialize;
+
+use crate::formatters::LB_PER_KG;
+use crate::models::{self, EBlock, Exercise, ExerciseWrapper, JDay, Set};
+
+// values a single ramp may expand to
+const MAX_RAMP: usize = 100;
+
+// unit of weights written without a suffix
+#[derive(clap::ValueEnum, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Unit {
+    #[default]
+    Kg,
+    Lb,
+}
+
+// where and why a log failed to parse; line and col are 1-based, col and
+// len count characters
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub col: usize,
+    pub len: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.col, self.message)
+    }
+}
+
+impl ParseError {
+    // compiler-style report pointing at the offending text
+    pub fn render(&self, path: &str, text: &str) -> String {
+        let source = text.lines().nth(self.line - 1).unwrap_or("");
+        let gutter = " ".repeat(self.line.to_string().len());
+        format!(
+            "error: {}\n{}--> {}:{}:{}\n{} |\n{} | {}\n{} | {}{}",
+            self.message, gutter, path, self.line, self.col,
+            gutter, self.line, source,
+            gutter, " ".repeat(self.col - 1), "^".repeat(self.len.max(1)),
+        )
+    }
+}
+
+// A workout read from a plain-text log: the date line, if there is one, and
+// the day in the shape the API returns, weights in kg.
+#[derive(Debug, Clone)]
+pub struct Workout {
+    pub date: Option<String>,
+    pub jday: JDay,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Tok {
+    Num(f32),
+    // milliseconds
+    Dur(f32),
+    Word(String),
+    X,
+    Comma,
+    At,
+    Plus,
+    Minus,
+    Bw,
+    // ".." of a ramp
+    Range,
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    tok: Tok,
+    // byte offsets into the line
+    start: usize,
+    end: usize,
+}
+
+// error span in byte offsets, turned into a ParseError once the line is known
+struct Span {
+    start: usize,
+    end: usize,
+    message: String,
+}
+
+fn span(start: usize, end: usize, message: &str) -> Span {
+    Span { start, end, message: message.to_string() }
+}
+
+fn scan_digits(chars: &[(usize, char)], mut i: usize) -> usize {
+    while i < chars.len() && chars[i].1.is_ascii_digit() {
+        i += 1;
+    }
+    i
+}
+
+fn lex(line: &str) -> Vec<Token> {
+    let chars: Vec<(usize, char)> = line.char_indices().collect();
+    let offset = |i: usize| chars.get(i).map_or(line.len(), |&(o, _)| o);
+    let at = |i: usize| chars.get(i).map(|&(_, c)| c);
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i].1;
+        let start = i;
+        let tok = if c.is_whitespace() {
+            i += 1;
+            continue;
+        } else if c.is_ascii_digit() {
+            i = scan_digits(&chars, i);
+            if at(i) == Some(':') && at(i + 1).is_some_and(|c| c.is_ascii_digit()) {
+                // m:ss or h:mm:ss, optionally with tenths
+                let mut secs: f32 = line[offset(start)..offset(i)].parse().unwrap_or(0.0);
+                for _ in 0..2 {
+                    if at(i) != Some(':') || !at(i + 1).is_some_and(|c| c.is_ascii_digit()) {
+                        break;
+                    }
+                    let from = i + 1;
+                    i = scan_digits(&chars, from);
+                    secs = secs * 60.0 + line[offset(from)..offset(i)].parse().unwrap_or(0.0);
+                }
+                if at(i) == Some('.') && at(i + 1).is_some_and(|c| c.is_ascii_digit()) {
+                    let from = i;
+                    i = scan_digits(&chars, i + 1);
+                    secs += line[offset(from)..offset(i)].parse().unwrap_or(0.0);
+                }
+                Tok::Dur(secs * 1000.0)
+            } else {
+                if at(i) == Some('.') && at(i + 1).is_some_and(|c| c.is_ascii_digit()) {
+                    i = scan_digits(&chars, i + 1);
+                }
+                Tok::Num(line[offset(start)..offset(i)].parse().unwrap_or(0.0))
+            }
+        } else if matches!(c, 'x' | 'X' | '×') && at(i + 1).is_none_or(|c| c.is_whitespace() || c.is_ascii_digit()) {
+            i += 1;
+            Tok::X
+        } else if c.eq_ignore_ascii_case(&'b') && at(i + 1).is_some_and(|c| c.eq_ignore_ascii_case(&'w'))
+            && at(i + 2).is_none_or(|c| !c.is_alphabetic()) {
+            i += 2;
+            Tok::Bw
+        } else if c == '.' && at(i + 1) == Some('.') {
+            i += 2;
+            Tok::Range
+        } else if matches!(c, ',' | '@' | '+' | '-') {
+            i += 1;
+            match c {
+                ',' => Tok::Comma,
+                '@' => Tok::At,
+                '+' => Tok::Plus,
+                _ => Tok::Minus,
+            }
+        } else if c.is_alphabetic() {
+            while at(i).is_some_and(|c| c.is_alphabetic() || c == '/') {
+                i += 1;
+            }
+            Tok::Word(line[offset(start)..offset(i)].to_string())
+        } else {
+            i += 1;
+            Tok::Word(c.to_string())
+        };
+        tokens.push(Token { tok, start: offset(start), end: offset(i) });
+    }
+    tokens
+}
+
+fn weight_unit(word: &str) -> Option<Unit> {
+    match word.to_lowercase().as_str() {
+        "kg" | "kgs" => Some(Unit::Kg),
+        "lb" | "lbs" => Some(Unit::Lb),
+        _ => None,
+    }
+}
+
+// "in" is left out: it introduces the time of a distance
+fn is_distance_unit(word: &str) -> bool {
+    matches!(word.to_lowercase().as_str(), "m" | "km" | "mi" | "mile" | "miles" | "yd" | "yds" | "ft" | "cm")
+}
+
+#[derive(Debug, Clone)]
+enum Quantity {
+    // a number without a unit: weight in the first part, a count after it
+    Plain(f32),
+    Weight { w: f32, unit: Unit, bw: bool },
+    Distance { d: f32, unit: String, t: Option<f32> },
+    Duration(f32),
+    // "60..140/20": evenly spaced plain numbers, or weights with a suffix
+    Ramp { values: Vec<f32>, unit: Option<Unit> },
+}
+
+impl Quantity {
+    fn is_time_distance(&self) -> bool {
+        matches!(self, Quantity::Distance { .. } | Quantity::Duration(_))
+    }
+}
+
+struct Value {
+    quantity: Quantity,
+    start: usize,
+    end: usize,
+}
+
+#[derive(Default)]
+struct Tail {
+    rpes: Vec<f32>,
+    rpe_span: (usize, usize),
+    speed: Option<f32>,
+    force: Option<f32>,
+    c: Option<String>,
+}
+
+struct SetParser<'a> {
+    line: &'a str,
+    tokens: Vec<Token>,
+    pos: usize,
+    unit: Unit,
+}
+
+impl SetParser<'_> {
+    fn peek(&self) -> Option<&Tok> {
+        self.tokens.get(self.pos).map(|t| &t.tok)
+    }
+
+    fn peek_word(&self, offset: usize) -> Option<&str> {
+        match self.tokens.get(self.pos + offset).map(|t| &t.tok) {
+            Some(Tok::Word(w)) => Some(w),
+            _ => None,
+        }
+    }
+
+    // span of the current token, or of the end of the line
+    fn here(&self) -> (usize, usize) {
+        match self.tokens.get(self.pos) {
+            Some(t) => (t.start, t.end),
+            None => (self.line.len(), self.line.len()),
+        }
+    }
+
+    fn number(&mut self, what: &str) -> Result<f32, Span> {
+        match self.peek() {
+            Some(&Tok::Num(v)) => {
+                self.pos += 1;
+                Ok(v)
+            }
+            _ => {
+                let (start, end) = self.here();
+                Err(span(start, end, &format!("expected {}", what)))
+            }
+        }
+    }
+
+    fn suffix_unit(&mut self) -> Option<Unit> {
+        let unit = self.peek_word(0).and_then(weight_unit)?;
+        self.pos += 1;
+        Some(unit)
+    }
+
+    fn quantity(&mut self, after: &str) -> Result<Value, Span> {
+        let (start, end) = self.here();
+        let quantity = match self.peek().cloned() {
+            Some(Tok::Bw) => {
+                self.pos += 1;
+                let sign = match self.peek() {
+                    Some(Tok::Plus) => Some(1.0),
+                    Some(Tok::Minus) => Some(-1.0),
+                    _ => None,
+                };
+                let w = match sign {
+                    Some(sign) => {
+                        self.pos += 1;
+                        sign * self.number("a weight after the sign")?
+                    }
+                    // "BW25" and "BW0" as written by some clients
+                    None if matches!(self.peek(), Some(Tok::Num(_))) => self.number("a weight")?,
+                    None => 0.0,
+                };
+                let unit = self.suffix_unit().unwrap_or(self.unit);
+                Quantity::Weight { w, unit, bw: true }
+            }
+            Some(Tok::Num(v)) => {
+                self.pos += 1;
+                if self.peek() == Some(&Tok::Range) {
+                    self.pos += 1;
+                    let values = self.ramp(v, start)?;
+                    Quantity::Ramp { values, unit: self.suffix_unit() }
+                } else if let Some(unit) = self.suffix_unit() {
+                    Quantity::Weight { w: v, unit, bw: false }
+                } else if let Some(unit) = self.peek_word(0).filter(|w| is_distance_unit(w)) {
+                    let unit = unit.to_lowercase();
+                    self.pos += 1;
+                    let mut t = None;
+                    if self.peek_word(0).is_some_and(|w| w.eq_ignore_ascii_case("in"))
+                        && let Some(&Tok::Dur(ms)) = self.tokens.get(self.pos + 1).map(|t| &t.tok) {
+                        self.pos += 2;
+                        t = Some(ms);
+                    }
+                    Quantity::Distance { d: v, unit, t }
+                } else {
+                    Quantity::Plain(v)
+                }
+            }
+            Some(Tok::Dur(ms)) => {
+                self.pos += 1;
+                Quantity::Duration(ms)
+            }
+            _ => return Err(span(start, end, &format!("expected a number after {}", after))),
+        };
+        let end = self.tokens[self.pos - 1].end;
+        Ok(Value { quantity, start, end })
+    }
+
+    // the values of "from..to/step", counting down when to < from; the
+    // step defaults to 1
+    fn ramp(&mut self, from: f32, start: usize) -> Result<Vec<f32>, Span> {
+        let to = self.number("the end of the ramp after '..'")?;
+        let mut step = 1.0;
+        if self.peek_word(0) == Some("/") {
+            self.pos += 1;
+            step = self.number("a step after '/'")?;
+        }
+        let end = self.tokens[self.pos - 1].end;
+        let steps = (to - from).abs() / step;
+        if step <= 0.0 || (steps - steps.round()).abs() > 0.001 {
+            return Err(span(start, end, &format!("{}..{} can't be walked in steps of {}", from, to, step)));
+        }
+        if steps.round() >= MAX_RAMP as f32 {
+            return Err(span(start, end, &format!("ramps are limited to {} values", MAX_RAMP)));
+        }
+        let sign = if to < from { -1.0 } else { 1.0 };
+        Ok((0..=steps.round() as usize).map(|k| from + sign * step * k as f32).collect())
+    }
+
+    // comma separated values of one 'x' part, ramps spelled out
+    fn part(&mut self, after: &str) -> Result<Vec<Value>, Span> {
+        let mut values = Vec::new();
+        let mut after = after;
+        loop {
+            let value = self.quantity(after)?;
+            match value.quantity {
+                Quantity::Ramp { values: ramp, unit } => values.extend(ramp.into_iter().map(|v| Value {
+                    quantity: match unit {
+                        Some(unit) => Quantity::Weight { w: v, unit, bw: false },
+                        None => Quantity::Plain(v),
+                    },
+                    start: value.start,
+                    end: value.end,
+                })),
+                _ => values.push(value),
+            }
+            if self.peek() != Some(&Tok::Comma) {
+                return Ok(values);
+            }
+            self.pos += 1;
+            after = "','";
+        }
+    }
+
+    fn parts(&mut self) -> Result<Vec<Vec<Value>>, Span> {
+        let mut parts = vec![self.part("the start")?];
+        while self.peek() == Some(&Tok::X) {
+            self.pos += 1;
+            parts.push(self.part("'x'")?);
+        }
+        Ok(parts)
+    }
+
+    // RPE, velocity readings and the free-form comment after the set
+    fn tail(&mut self) -> Result<Tail, Span> {
+        let mut tail = Tail::default();
+        loop {
+            match self.peek().cloned() {
+                Some(Tok::At) => {
+                    self.pos += 1;
+                    self.rpes(&mut tail)?;
+                }
+                Some(Tok::Word(w)) if w.eq_ignore_ascii_case("rpe") && matches!(self.tokens.get(self.pos + 1).map(|t| &t.tok), Some(Tok::Num(_))) => {
+                    self.pos += 1;
+                    self.rpes(&mut tail)?;
+                }
+                Some(Tok::Word(w)) if w == "★" => self.pos += 1,
+                Some(Tok::Num(v)) if self.peek_word(1) == Some("m/s") => {
+                    self.pos += 2;
+                    tail.speed = Some(v);
+                }
+                Some(Tok::Num(v)) if self.peek_word(1) == Some("N") => {
+                    self.pos += 2;
+                    tail.force = Some(v);
+                }
+                _ => break,
+            }
+        }
+        if let Some(token) = self.tokens.get(self.pos) {
+            tail.c = Some(self.line[token.start..].trim().to_string());
+        }
+        Ok(tail)
+    }
+
+    // "8" or, one per set, "7, 8, 9"
+    fn rpes(&mut self, tail: &mut Tail) -> Result<(), Span> {
+        let (start, _) = self.here();
+        tail.rpes = vec![self.rpe()?];
+        while self.peek() == Some(&Tok::Comma) && matches!(self.tokens.get(self.pos + 1).map(|t| &t.tok), Some(Tok::Num(_))) {
+            self.pos += 1;
+            tail.rpes.push(self.rpe()?);
+        }
+        tail.rpe_span = (start, self.tokens[self.pos - 1].end);
+        Ok(())
+    }
+
+    fn rpe(&mut self) -> Result<f32, Span> {
+        let (start, end) = self.here();
+        let rpe = self.number("an RPE")?;
+        if !(1.0..=10.0).contains(&rpe) {
+            return Err(span(start, end, "RPE must be between 1 and 10"));
+        }
+        Ok(rpe)
+    }
+}
+
+fn to_kg(w: f32, unit: Unit) -> f32 {
+    match unit {
+        Unit::Kg => w,
+        Unit::Lb => w / LB_PER_KG,
+    }
+}
+
+fn lb_flag(unit: Unit) -> Option<f32> {
+    Some(if unit == Unit::Lb { 1.0 } else { 0.0 })
+}
+
+fn count(value: &Value, what: &str) -> Result<u32, Span> {
+    match value.quantity {
+        Quantity::Plain(v) if v.fract() == 0.0 => Ok(v as u32),
+        _ => Err(span(value.start, value.end, &format!("{} must be a whole number", what))),
+    }
+}
+
+fn single<'a>(part: &'a [Value], what: &str) -> Result<&'a Value, Span> {
+    match part {
+        [value] => Ok(value),
+        _ => Err(span(part[0].start, part[part.len() - 1].end, &format!("only one {} allowed here", what))),
+    }
+}
+
+fn load(value: &Value, unit: Unit, set: &mut Set) -> Result<(), Span> {
+    let (w, unit, bw) = match value.quantity {
+        Quantity::Plain(w) => (w, unit, false),
+        Quantity::Weight { w, unit, bw } => (w, unit, bw),
+        _ => return Err(span(value.start, value.end, "expected a weight")),
+    };
+    set.w = Some(to_kg(w, unit));
+    set.lb = lb_flag(unit);
+    if bw {
+        set.ubw = Some(1);
+    }
+    Ok(())
+}
+
+// One line of sets: "135 x 10", "225, 315 x 5", "445 x 1, 3", "175 x 10 x 3",
+// "BW+25 x 5 @8", "5 km in 25:30", "2:00 x 3", "100 x 40 m", followed by an
+// optional comment. Lists expand to one set each.
+fn parse_sets(line: &str, unit: Unit) -> Result<Vec<Set>, Span> {
+    let mut parser = SetParser { line, tokens: lex(line), pos: 0, unit };
+    let parts = parser.parts()?;
+    let mut base = Set { s: Some(1), lb: lb_flag(unit), ..Default::default() };
+    let mut sets = Vec::new();
+    let timed = parts.iter().position(|p| p.iter().any(|v| v.quantity.is_time_distance()));
+    match timed {
+        None => {
+            if let Some(extra) = parts.get(3) {
+                return Err(span(extra[0].start, extra[0].end, "too many 'x' parts, expected weight x reps x sets"));
+            }
+            if let Some(s) = parts.get(2) {
+                base.s = Some(count(single(s, "set count")?, "sets")?);
+            }
+            let reps = parts.get(1).map(|p| p.as_slice()).unwrap_or(&[]);
+            if parts[0].len() > 1 && reps.len() > 1 {
+                return Err(span(parts[0][0].start, reps[reps.len() - 1].end, "weights and reps can't both be lists"));
+            }
+            for value in &parts[0] {
+                let mut set = base.clone();
+                load(value, unit, &mut set)?;
+                if reps.len() > 1 {
+                    for r in reps {
+                        sets.push(Set { r: Some(count(r, "reps")?), ..set.clone() });
+                    }
+                } else {
+                    set.r = reps.first().map(|r| count(r, "reps")).transpose()?;
+                    sets.push(set);
+                }
+            }
+        }
+        Some(k) => {
+            if k > 2 {
+                return Err(span(parts[k][0].start, parts[k][0].end, "a time or distance comes after at most a weight and reps"));
+            }
+            if let Some(extra) = parts.get(k + 2) {
+                return Err(span(extra[0].start, extra[0].end, "too many 'x' parts after the time or distance"));
+            }
+            let mut set = base;
+            if k >= 1 {
+                load(single(&parts[0], "weight")?, unit, &mut set)?;
+            }
+            if k == 2 {
+                set.r = Some(count(single(&parts[1], "rep count")?, "reps")?);
+            }
+            let value = single(&parts[k], "time or distance")?;
+            match &value.quantity {
+                Quantity::Distance { d, unit, t } => {
+                    set.d = Some(*d);
+                    set.dunit = Some(unit.clone());
+                    set.t = *t;
+                }
+                Quantity::Duration(t) => set.t = Some(*t),
+                _ => return Err(span(value.start, value.end, "expected a time or distance")),
+            }
+            if let Some(s) = parts.get(k + 1) {
+                set.s = Some(count(single(s, "set count")?, "sets")?);
+            }
+            sets.push(set);
+        }
+    }
+    // one RPE applies to every set of the line, a list gives one per set
+    // (splitting "100 x 5 x 3 @7, 8, 9" into three sets); the comment goes
+    // to the last set
+    let tail = parser.tail()?;
+    let n = tail.rpes.len();
+    if n > 1 && sets.len() == 1 && sets[0].s == Some(n as u32) {
+        sets = vec![Set { s: Some(1), ..sets[0].clone() }; n];
+    }
+    if n > 1 && n != sets.len() {
+        let count = if sets.len() == 1 { sets[0].s.unwrap_or(1) as usize } else { sets.len() };
+        return Err(span(tail.rpe_span.0, tail.rpe_span.1, &format!("{} RPEs given for {} sets", n, count)));
+    }
+    for (k, set) in sets.iter_mut().enumerate() {
+        set.rpe = tail.rpes.get(if n > 1 { k } else { 0 }).copied();
+        set.speed = tail.speed;
+        set.force = tail.force;
+    }
+    if let Some(last) = sets.last_mut() {
+        last.c = tail.c;
+    }
+    Ok(sets)
+}
+
+// lines inside a block that must parse as sets
+fn looks_like_sets(line: &str) -> bool {
+    let mut chars = line.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_digit() => true,
+        Some('b' | 'B') => chars.next().is_some_and(|c| c.eq_ignore_ascii_case(&'w'))
+            && chars.next().is_none_or(|c| !c.is_alphabetic()),
+        _ => false,
+    }
+}
+
+// "@ 222 bw", in the file's unit unless a suffix says otherwise
+fn parse_bodyweight(line: &str, unit: Unit) -> Option<Result<f32, Span>> {
+    let lower = line.to_lowercase();
+    if !line.starts_with('@') || !lower.ends_with("bw") {
+        return None;
+    }
+    let body = &line[1..line.len() - 2];
+    let start = 1 + body.len() - body.trim_start().len();
+    let text = body.trim();
+    let (number, unit) = match weight_unit(text.trim_start_matches(|c: char| c.is_ascii_digit() || c == '.').trim()) {
+        Some(u) => (text.trim_end_matches(char::is_alphabetic).trim_end(), u),
+        None => (text, unit),
+    };
+    Some(match number.parse::<f32>() {
+        Ok(bw) if bw > 0.0 => Ok(to_kg(bw, unit)),
+        _ => Err(span(start, start + text.len().max(1), "expected a bodyweight, as in '@ 80 bw'")),
+    })
+}
+
+fn to_error(line_no: usize, line: &str, span: Span) -> ParseError {
+    let col = line[..span.start].chars().count() + 1;
+    let len = line[span.start..span.end].chars().count().max(1);
+    ParseError { line: line_no, col, len, message: span.message }
+}
+
+// Reads a log as `show` prints it: an optional date line and "@ N bw" line,
+// free text, and blocks made of a "#exercise #tags" line followed by set
+// lines. A blank line or free text ends a block; "//" comments do not, the
+// sets after one being read as a second block of the same exercise.
+pub fn parse(text: &str, unit: Unit) -> Result<Workout, ParseError> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut i = lines.iter().take_while(|l| l.trim().is_empty()).count();
+    let mut date = None;
+    if let Some(first) = lines.get(i)
+        && let Ok(d) = NaiveDate::parse_from_str(first.trim(), "%Y-%m-%d") {
+        date = Some(d.to_string());
+        i += 1;
+    }
+    let mut bw = None;
+    let mut log = Vec::new();
+    let mut eblocks: Vec<EBlock> = Vec::new();
+    let mut exercises = Vec::new();
+    let mut in_block = false;
+    for (n, &raw) in lines.iter().enumerate().skip(i) {
+        let line_no = n + 1;
+        let trimmed = raw.trim();
+        let indent = raw.len() - raw.trim_start().len();
+        let shift = |s: Span| Span { start: s.start + indent, end: s.end + indent, message: s.message };
+        if trimmed.starts_with('#') {
+            let name = trimmed.trim_start_matches('#').split_whitespace().collect::<Vec<_>>().join(" ");
+            if name.is_empty() {
+                return Err(to_error(line_no, raw, span(indent, indent + 1, "missing exercise name after '#'")));
+            }
+            // the same exercise gets the same id on every day
+            let eid = models::normalize_name(&name);
+            if !exercises.iter().any(|e: &ExerciseWrapper| e.exercise.id == eid) {
+                exercises.push(ExerciseWrapper {
+                    exercise: Exercise { id: eid.clone(), name, ex_type: None },
+                });
+            }
+            eblocks.push(EBlock { eid: eid.clone(), sets: Vec::new() });
+            log.push(format!("EBLOCK:{}", eid));
+            in_block = true;
+        } else if in_block && looks_like_sets(trimmed) {
+            let sets = parse_sets(trimmed, unit).map_err(|s| to_error(line_no, raw, shift(s)))?;
+            // sets after a comment go on in a block of their own, so the
+            // comment keeps its place among them
+            if log.last().is_some_and(|l: &String| l.trim_start().starts_with("//"))
+                && let Some(eid) = eblocks.last().map(|b| b.eid.clone()) {
+                eblocks.push(EBlock { eid: eid.clone(), sets: Vec::new() });
+                log.push(format!("EBLOCK:{}", eid));
+            }
+            if let Some(block) = eblocks.last_mut() {
+                block.sets.extend(sets);
+            }
+        } else if bw.is_none() && eblocks.is_empty()
+            && let Some(result) = parse_bodyweight(trimmed, unit) {
+            bw = Some(result.map_err(|s| to_error(line_no, raw, shift(s)))?);
+        } else {
+            in_block &= trimmed.starts_with("//");
+            log.push(raw.to_string());
+        }
+    }
+    let log = log.join("\n").trim_end().to_string();
+    Ok(Workout { date, jday: JDay { log, bw, eblocks, exercises } })
+}
@@ -0,0 +1,116 @@
+use std::collections::BTreeMap;
+
+use crate::aliases::{self, Aliases};
+use crate::formatters;
+use crate::metrics;
+use crate::models::{JDay, Set};
+
+// rep counts reported in the rep-max table
+pub const REP_MAXES: [u32; 6] = [1, 2, 3, 5, 8, 10];
+
+// weights are in kg; `server_pr` is the server's own PR flag on that set
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    pub weight: f32,
+    pub reps: u32,
+    pub date: String,
+    pub server_pr: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct ExerciseRecords {
+    // best weight moved for at least REP_MAXES[i] reps
+    pub rep_maxes: [Option<Record>; REP_MAXES.len()],
+    // `weight` holds the estimated 1RM
+    pub best_e1rm: Option<Record>,
+}
+
+// only strictly better lifts replace a record, so ties keep the first date;
+// true when `candidate` beat a record already there
+fn improve(slot: &mut Option<Record>, candidate: Record) -> bool {
+    match slot {
+        Some(r) if candidate.weight <= r.weight => false,
+        _ => slot.replace(candidate).is_some(),
+    }
+}
+
+// adds a set to an exercise's records; true when it beat one of them, so a
+// first set of an exercise is not a PR
+pub fn add_set(records: &mut ExerciseRecords, date: &str, set: &Set) -> bool {
+    let w = set.w.unwrap_or(0.0);
+    let r = set.r.unwrap_or(0);
+    if !set.is_weight_reps() || set.is_bodyweight() || w <= 0.0 || r == 0 {
+        return false;
+    }
+    let server_pr = set.pr.unwrap_or(0) > 0;
+    let mut beaten = false;
+    for (i, &n) in REP_MAXES.iter().enumerate() {
+        if r >= n {
+            beaten |= improve(&mut records.rep_maxes[i], Record { weight: w, reps: r, date: date.to_string(), server_pr });
+        }
+    }
+    beaten |= improve(&mut records.best_e1rm, Record { weight: metrics::set_e1rm(set), reps: r, date: date.to_string(), server_pr });
+    beaten
+}
+
+// `days` are (YYYY-MM-DD, workout) pairs, oldest first. The `exercise` query
+// is matched against each block as logged; with `aliases`, records are then
+// kept per parent lift.
+pub fn build_records(days: &[(String, JDay)], exercise: Option<&str>, aliases: Option<&Aliases>) -> BTreeMap<String, ExerciseRecords> {
+    let mut all: BTreeMap<String, ExerciseRecords> = BTreeMap::new();
+    for (date, jday) in days {
+        for eblock in &jday.eblocks {
+            let Some(ex) = jday.exercise(&eblock.eid) else {
+                continue;
+            };
+            if exercise.is_some_and(|q| !aliases::block_matches(jday, eblock, q, aliases)) {
+                continue;
+            }
+            let name = aliases.and_then(|a| a.resolve(jday, eblock)).unwrap_or_else(|| ex.name.clone());
+            let records = all.entry(name).or_default();
+            for set in &eblock.sets {
+                add_set(records, date, set);
+            }
+        }
+    }
+    all.retain(|_, r| r.best_e1rm.is_some());
+    all
+}
+
+fn record_cells(label: String, record: &Record, lb: bool) -> Vec<String> {
+    vec![
+        label,
+        formatters::format_weight(record.weight, lb),
+        record.reps.to_string(),
+        formatters::color_date(&record.date),
+        if record.server_pr { "✓".to_string() } else { String::new() },
+    ]
+}
+
+// one block of rows per exercise: each rep max, then the best e1RM;
+// the last column shows where the server also flagged the set as a PR
+pub fn render_records(all: &BTreeMap<String, ExerciseRecords>, lb: bool) -> String {
+    let headers = ["exercise", "max", "weight", "reps", "date", "server PR"];
+    let mut rows = Vec::new();
+    for (name, records) in all {
+        let mut first = true;
+        let mut exercise_cell = || {
+            let cell = if first { formatters::color_exercise(name) } else { String::new() };
+            first = false;
+            cell
+        };
+        for (i, record) in records.rep_maxes.iter().enumerate() {
+            if let Some(record) = record {
+                let mut row = vec![exercise_cell()];
+                row.extend(record_cells(format!("{}RM", REP_MAXES[i]), record, lb));
+                rows.push(row);
+            }
+        }
+        if let Some(record) = &records.best_e1rm {
+            let mut row = vec![exercise_cell()];
+            row.extend(record_cells("e1RM".to_string(), record, lb));
+            rows.push(row);
+        }
+    }
+    formatters::render_table(&headers, &rows)
+}
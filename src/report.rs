@@ -0,0 +1,237 @@
+use chrono::{Datelike, NaiveDate};
+
+use crate::formatters;
+use crate::metrics;
+use crate::models::{JDay, Set};
+use crate::records;
+use crate::stats::{self, GroupBy, Totals};
+use crate::utils;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Period {
+    Week,
+    Month,
+    Year,
+}
+
+// first and last day of the period holding `date`; weeks run Monday to Sunday
+pub fn period_bounds(period: Period, date: NaiveDate) -> Result<(NaiveDate, NaiveDate), String> {
+    match period {
+        Period::Week => {
+            let monday: NaiveDate = stats::period_label(date, GroupBy::Week).parse().map_err(|_| "Invalid date")?;
+            Ok((monday, monday + chrono::Duration::days(6)))
+        }
+        Period::Month => utils::parse_date_range(&format!("{:04}-{:02}", date.year(), date.month())),
+        Period::Year => utils::parse_date_range(&format!("{:04}", date.year())),
+    }
+}
+
+// the period just before the one starting at `oldest`
+pub fn previous_bounds(period: Period, oldest: NaiveDate) -> Result<(NaiveDate, NaiveDate), String> {
+    period_bounds(period, oldest - chrono::Duration::days(1))
+}
+
+pub fn title(period: Period, oldest: NaiveDate) -> String {
+    match period {
+        Period::Week => format!("Week of {}", oldest),
+        Period::Month => oldest.format("%B %Y").to_string(),
+        Period::Year => format!("{} in review", oldest.year()),
+    }
+}
+
+pub struct ExerciseSummary {
+    pub name: String,
+    // heaviest set, then most reps
+    pub top: Option<Set>,
+    pub totals: Totals,
+}
+
+// a set worth pointing out, with its date and exercise
+pub struct Highlight {
+    pub date: String,
+    pub exercise: String,
+    pub set: Set,
+}
+
+pub struct Report {
+    pub title: String,
+    pub oldest: NaiveDate,
+    pub latest: NaiveDate,
+    pub total: Totals,
+    pub previous: Totals,
+    // first and last bodyweight logged in the period, in kg
+    pub bw: Option<(f32, f32)>,
+    pub exercises: Vec<ExerciseSummary>,
+    pub prs: Vec<Highlight>,
+    pub comments: Vec<Highlight>,
+}
+
+fn period_totals(days: &[(String, JDay)]) -> Totals {
+    let mut total = Totals { sessions: days.len() as u32, ..Default::default() };
+    for (_, jday) in days {
+        for eblock in &jday.eblocks {
+            total.add(&eblock.sets);
+        }
+    }
+    total
+}
+
+// `days`, `previous_days` and `earlier` (everything before the period) are
+// (YYYY-MM-DD, workout) pairs, oldest first. A set is a PR when it beats the
+// records set before it; the server's flag only counts for an exercise with
+// no records before the period to compare against.
+pub fn build_report(period: Period, oldest: NaiveDate, latest: NaiveDate, days: &[(String, JDay)], previous_days: &[(String, JDay)], earlier: &[(String, JDay)]) -> Report {
+    let mut bests = records::build_records(earlier, None, None);
+    let known: Vec<String> = bests.keys().cloned().collect();
+    let mut exercises: Vec<ExerciseSummary> = Vec::new();
+    let mut prs = Vec::new();
+    let mut comments = Vec::new();
+    for (date, jday) in days {
+        for (name, totals) in stats::day_totals(jday) {
+            match exercises.iter_mut().find(|e| e.name == name) {
+                Some(e) => {
+                    e.totals.sessions += totals.sessions;
+                    e.totals.hard_sets += totals.hard_sets;
+                    e.totals.reps += totals.reps;
+                    e.totals.tonnage += totals.tonnage;
+                    e.totals.top_e1rm = e.totals.top_e1rm.max(totals.top_e1rm);
+                }
+                None => exercises.push(ExerciseSummary { name, top: None, totals }),
+            }
+        }
+        for eblock in &jday.eblocks {
+            let Some(ex) = jday.exercise(&eblock.eid) else {
+                continue;
+            };
+            let mut summary = exercises.iter_mut().find(|e| e.name == ex.name);
+            let best = bests.entry(ex.name.clone()).or_default();
+            for set in &eblock.sets {
+                if let Some(summary) = summary.as_deref_mut()
+                    && set.is_weight_reps() {
+                    // on a tie the earlier set stays
+                    summary.top = metrics::top_set([set].into_iter().chain(summary.top.as_ref())).cloned();
+                }
+                let highlight = || Highlight { date: date.clone(), exercise: ex.name.clone(), set: set.clone() };
+                let beaten = records::add_set(best, date, set);
+                if beaten || (!known.contains(&ex.name) && set.pr.unwrap_or(0) > 0) {
+                    prs.push(highlight());
+                }
+                if set.c.as_ref().is_some_and(|c| !c.trim().is_empty()) {
+                    comments.push(highlight());
+                }
+            }
+        }
+    }
+    let weights: Vec<f32> = days.iter().filter_map(|(_, j)| j.bw).filter(|&w| w > 0.0).collect();
+    Report {
+        title: title(period, oldest),
+        oldest,
+        latest,
+        total: period_totals(days),
+        previous: period_totals(previous_days),
+        bw: weights.first().zip(weights.last()).map(|(a, b)| (*a, *b)),
+        exercises,
+        prs,
+        comments,
+    }
+}
+
+fn rounded_delta(delta: f32) -> String {
+    formatters::format_delta((delta * 10.0).round() / 10.0)
+}
+
+fn summary_lines(report: &Report, lb: bool, user_bw: impl Fn(f32) -> f32) -> Vec<(&'static str, String)> {
+    let (now, before) = (&report.total, &report.previous);
+    let mut lines = vec![
+        ("sessions", format!("{} ({} vs previous)", now.sessions, rounded_delta(now.sessions as f32 - before.sessions as f32))),
+        ("hard sets", format!("{} ({})", now.hard_sets, rounded_delta(now.hard_sets as f32 - before.hard_sets as f32))),
+        ("tonnage", format!(
+            "{} ({})",
+            formatters::format_weight(now.tonnage, lb),
+            rounded_delta(((now.tonnage - before.tonnage) * if lb { formatters::LB_PER_KG } else { 1.0 }).round())
+        )),
+    ];
+    if let Some((first, last)) = report.bw {
+        let (first, last) = (user_bw(first), user_bw(last));
+        lines.push(("bodyweight", format!("{:.1} → {:.1} ({})", first, last, rounded_delta(last - first))));
+    }
+    lines
+}
+
+// "#squat", in the table and the highlights alike
+fn exercise_label(name: &str) -> String {
+    format!("#{}", formatters::color_exercise(name))
+}
+
+fn exercise_rows(report: &Report, lb: bool) -> Vec<Vec<String>> {
+    report.exercises.iter()
+        .map(|e| vec![
+            exercise_label(&e.name),
+            e.top.as_ref().map(formatters::format_set).unwrap_or_default(),
+            e.totals.sessions.to_string(),
+            e.totals.hard_sets.to_string(),
+            formatters::format_weight(e.totals.tonnage, lb),
+        ])
+        .collect()
+}
+
+const EXERCISE_HEADERS: [&str; 5] = ["exercise", "top set", "sessions", "hard sets", "tonnage"];
+
+// `user_bw` converts a bodyweight in kg to the unit shown
+pub fn render_text(report: &Report, lb: bool, user_bw: impl Fn(f32) -> f32) -> String {
+    let mut out = vec![format!(
+        "{} ({} → {})",
+        report.title,
+        formatters::color_date(&report.oldest.to_string()),
+        formatters::color_date(&report.latest.to_string())
+    )];
+    out.push(String::new());
+    let summary = summary_lines(report, lb, user_bw);
+    let width = summary.iter().map(|(l, _)| l.len()).max().unwrap_or(0);
+    out.extend(summary.iter().map(|(label, value)| format!("{:<width$}  {}", label, value)));
+    if !report.exercises.is_empty() {
+        out.push(String::new());
+        out.push(formatters::render_table(&EXERCISE_HEADERS, &exercise_rows(report, lb)));
+    }
+    for (heading, highlights) in [("PRs", &report.prs), ("Comments", &report.comments)] {
+        if highlights.is_empty() {
+            continue;
+        }
+        out.push(String::new());
+        out.push(heading.to_string());
+        for h in highlights {
+            out.push(format!(
+                "  {}  {}  {}",
+                formatters::color_date(&h.date),
+                exercise_label(&h.exercise),
+                formatters::format_set(&h.set)
+            ));
+        }
+    }
+    out.join("\n")
+}
+
+// expects colors to be off so no escape codes end up in the document
+pub fn render_markdown(report: &Report, lb: bool, user_bw: impl Fn(f32) -> f32) -> String {
+    let mut out = vec![format!("# {}", report.title), String::new(), format!("{} → {}", report.oldest, report.latest)];
+    out.push(String::new());
+    out.push("## Summary".to_string());
+    out.push(String::new());
+    out.extend(summary_lines(report, lb, user_bw).iter().map(|(label, value)| format!("- **{}**: {}", label, value)));
+    if !report.exercises.is_empty() {
+        out.push(String::new());
+        out.push("## Exercises".to_string());
+        out.push(String::new());
+        out.push(formatters::render_markdown_table(&EXERCISE_HEADERS, &exercise_rows(report, lb)));
+    }
+    for (heading, highlights) in [("PRs", &report.prs), ("Comments", &report.comments)] {
+        if highlights.is_empty() {
+            continue;
+        }
+        out.push(String::new());
+        out.push(format!("## {}", heading));
+        out.push(String::new());
+        out.extend(highlights.iter().map(|h| format!("- {} {}: {}", h.date, exercise_label(&h.exercise), formatters::format_set(&h.set))));
+    }
+    out.join("\n")
+}
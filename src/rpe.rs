@@ -0,0 +1,205 @@
+use std::collections::{BTreeMap, HashMap};
+use chrono::NaiveDate;
+
+use crate::formatters;
+use crate::models::{JDay, Set};
+use crate::stats::{self, GroupBy};
+
+// reps covered by the default table
+pub const TABLE_REPS: u32 = 12;
+// an RPE this much higher at the same weight x reps gets flagged
+pub const RPE_DRIFT: f32 = 1.0;
+
+// Tuchscherer's RPE chart in half-rep steps: a set of r reps at RPE x sits at
+// index 2 * ((r - 1) + (10 - x)), i.e. reps done plus reps left in the tank
+const CHART: [f32; 31] = [
+    100.0, 97.8, 95.5, 93.9, 92.2, 90.7, 89.2, 87.8, 86.3, 85.0, 83.7, 82.4, 81.1, 79.9, 78.6, 77.4,
+    76.2, 75.1, 73.9, 72.3, 70.7, 69.4, 68.0, 66.7, 65.3, 64.0, 62.6, 61.3, 59.9, 58.6, 57.4,
+];
+
+// Percentage of 1RM by RPE and reps, RPE 10 first. Defaults to Tuchscherer's
+// chart; rows can be replaced from the config file.
+#[derive(Debug, Clone)]
+pub struct RpeTable {
+    rows: Vec<(f32, Vec<f32>)>,
+}
+
+impl Default for RpeTable {
+    fn default() -> Self {
+        let rows = (0..=8)
+            .map(|i| 10.0 - i as f32 * 0.5)
+            .map(|rpe| {
+                let steps = ((10.0 - rpe) * 2.0) as usize;
+                (rpe, (0..TABLE_REPS as usize).map(|r| CHART[2 * r + steps]).collect())
+            })
+            .collect();
+        RpeTable { rows }
+    }
+}
+
+impl RpeTable {
+    // `overrides` maps an RPE ("8.5") to percentages for 1, 2, 3... reps
+    pub fn with_overrides(overrides: &BTreeMap<String, Vec<f32>>) -> Result<Self, String> {
+        let mut table = RpeTable::default();
+        for (key, percents) in overrides {
+            let rpe: f32 = key.parse().map_err(|_| format!("Invalid RPE '{}' in rpe_table", key))?;
+            if !(1.0..=10.0).contains(&rpe) || (rpe * 2.0).fract() != 0.0 {
+                return Err(format!("RPE '{}' in rpe_table must be between 1 and 10 in steps of 0.5", key));
+            }
+            if percents.is_empty() || percents.iter().any(|&p| p <= 0.0 || p > 100.0) {
+                return Err(format!("Percentages for RPE {} in rpe_table must be above 0 and at most 100", key));
+            }
+            table.rows.retain(|(r, _)| *r != rpe);
+            table.rows.push((rpe, percents.clone()));
+        }
+        table.rows.sort_by(|a, b| b.0.total_cmp(&a.0));
+        Ok(table)
+    }
+
+    pub fn percent(&self, reps: u32, rpe: f32) -> Option<f32> {
+        let (_, percents) = self.rows.iter().find(|(r, _)| (r - rpe).abs() < 0.01)?;
+        percents.get((reps as usize).checked_sub(1)?).copied()
+    }
+
+    // only sets that carry an RPE the table covers get an estimate
+    pub fn e1rm(&self, set: &Set) -> Option<f32> {
+        let w = set.w.unwrap_or(0.0);
+        let r = set.r.unwrap_or(0);
+        let rpe = set.rpe.filter(|&x| x > 0.0)?;
+        if !set.is_weight_reps() || set.is_bodyweight() || w <= 0.0 {
+            return None;
+        }
+        self.percent(r, rpe).map(|pct| w * 100.0 / pct)
+    }
+
+    pub fn render(&self) -> String {
+        let columns = self.rows.iter().map(|(_, p)| p.len()).max().unwrap_or(0);
+        let reps: Vec<String> = (1..=columns).map(|r| r.to_string()).collect();
+        let mut headers = vec!["RPE", "RIR"];
+        headers.extend(reps.iter().map(|r| r.as_str()));
+        let rows: Vec<Vec<String>> = self.rows.iter()
+            .map(|(rpe, percents)| {
+                let mut row = vec![formatters::format_number(*rpe), formatters::format_number(10.0 - rpe)];
+                row.extend(percents.iter().map(|p| format!("{:.1}", p)));
+                row
+            })
+            .collect();
+        formatters::render_table(&headers, &rows)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeekRpe {
+    pub week: String,
+    pub exercise: String,
+    // sets logged with an RPE
+    pub sets: u32,
+    pub avg_rpe: f32,
+    // best RPE-based e1RM of the week, 0 when the table covers none of them
+    pub best_e1rm: f32,
+}
+
+// the same weight x reps done at a clearly higher RPE than last time
+#[derive(Debug, Clone)]
+pub struct Drift {
+    pub exercise: String,
+    pub set: Set,
+    pub before: (String, f32),
+    pub after: (String, f32),
+}
+
+fn matching_blocks<'a>(jday: &'a JDay, exercise: Option<&'a str>) -> impl Iterator<Item = (&'a str, &'a [Set])> {
+    jday.eblocks.iter()
+        .filter(move |b| exercise.is_none_or(|q| jday.eblock_matches(b, q)))
+        .filter_map(|b| jday.exercise(&b.eid).map(|ex| (ex.name.as_str(), b.sets.as_slice())))
+}
+
+// `days` are (YYYY-MM-DD, workout) pairs, oldest first
+pub fn weekly_rpe(days: &[(String, JDay)], exercise: Option<&str>, table: &RpeTable) -> Vec<WeekRpe> {
+    // (week, exercise) -> (sets, rpe sum, best e1RM)
+    let mut weeks: BTreeMap<(String, String), (u32, f32, f32)> = BTreeMap::new();
+    for (date, jday) in days {
+        let Ok(day) = date.parse::<NaiveDate>() else {
+            continue;
+        };
+        let week = stats::period_label(day, GroupBy::Week);
+        for (name, sets) in matching_blocks(jday, exercise) {
+            for set in sets.iter().filter(|s| s.rpe.unwrap_or(0.0) > 0.0) {
+                let n = set.s.unwrap_or(1).max(1);
+                let entry = weeks.entry((week.clone(), name.to_string())).or_default();
+                entry.0 += n;
+                entry.1 += set.rpe.unwrap_or(0.0) * n as f32;
+                entry.2 = entry.2.max(table.e1rm(set).unwrap_or(0.0));
+            }
+        }
+    }
+    weeks.into_iter()
+        .map(|((week, exercise), (sets, sum, best_e1rm))| WeekRpe { week, exercise, sets, avg_rpe: sum / sets as f32, best_e1rm })
+        .collect()
+}
+
+pub fn rpe_drift(days: &[(String, JDay)], exercise: Option<&str>) -> Vec<Drift> {
+    // (exercise, weight in grams, reps) -> (date, hardest RPE, set)
+    let mut last: HashMap<(String, i64, u32), (String, f32)> = HashMap::new();
+    let mut drifts = Vec::new();
+    for (date, jday) in days {
+        let mut session: BTreeMap<(String, i64, u32), (f32, Set)> = BTreeMap::new();
+        for (name, sets) in matching_blocks(jday, exercise) {
+            for set in sets.iter().filter(|s| s.is_weight_reps() && !s.is_bodyweight()) {
+                let rpe = set.rpe.unwrap_or(0.0);
+                if rpe <= 0.0 || set.w.unwrap_or(0.0) <= 0.0 {
+                    continue;
+                }
+                let key = (name.to_string(), (set.w.unwrap_or(0.0) * 1000.0).round() as i64, set.r.unwrap_or(0));
+                let entry = session.entry(key).or_insert((rpe, set.clone()));
+                entry.0 = entry.0.max(rpe);
+            }
+        }
+        for (key, (rpe, set)) in session {
+            if let Some((before_date, before_rpe)) = last.get(&key)
+                && rpe - before_rpe >= RPE_DRIFT {
+                drifts.push(Drift {
+                    exercise: key.0.clone(),
+                    set,
+                    before: (before_date.clone(), *before_rpe),
+                    after: (date.clone(), rpe),
+                });
+            }
+            last.insert(key, (date.clone(), rpe));
+        }
+    }
+    drifts
+}
+
+pub fn render_analysis(weeks: &[WeekRpe], drifts: &[Drift], lb: bool) -> String {
+    let mut rows = Vec::new();
+    let mut previous = "";
+    for w in weeks {
+        rows.push(vec![
+            if w.week != previous { w.week.clone() } else { String::new() },
+            formatters::color_exercise(&w.exercise),
+            w.sets.to_string(),
+            format!("{:.1}", w.avg_rpe),
+            if w.best_e1rm > 0.0 { formatters::format_weight(w.best_e1rm, lb) } else { String::new() },
+        ]);
+        previous = &w.week;
+    }
+    let mut out = vec![formatters::render_table(&["week", "exercise", "RPE sets", "avg RPE", "e1RM"], &rows)];
+    if !drifts.is_empty() {
+        out.push(String::new());
+        out.push("RPE drift at the same load:".to_string());
+        for d in drifts {
+            let load = format!("{} x {}", formatters::format_load(&d.set), d.set.r.unwrap_or(0));
+            out.push(format!(
+                "  #{}  {}  @{} → {}  ({} → {})",
+                formatters::color_exercise(&d.exercise),
+                load,
+                formatters::format_number(d.before.1),
+                formatters::color_delta(&format!("@{}", formatters::format_number(d.after.1)), -1.0),
+                formatters::color_date(&d.before.0),
+                formatters::color_date(&d.after.0)
+            ));
+        }
+    }
+    out.join("\n")
+}
@@ -0,0 +1,95 @@
+use regex::Regex;
+
+use crate::formatters;
+use crate::models::{JDay, Set};
+
+// what matched in one workout: log lines (with the requested context around
+// them) and set comments
+pub struct DayHits {
+    pub date: String,
+    pub lines: Vec<String>,
+}
+
+fn highlight(text: &str, re: &Regex) -> String {
+    re.replace_all(text, |caps: &regex::Captures| formatters::color_match(&caps[0])).to_string()
+}
+
+// log lines as the user sees them, with each block placeholder shown as the
+// exercise header
+fn log_lines(jday: &JDay) -> Vec<String> {
+    jday.log.lines()
+        .map(|line| {
+            let eblock = jday.eblocks.iter().find(|b| line.trim() == format!("EBLOCK:{}", b.eid));
+            match eblock.and_then(|b| jday.exercise(&b.eid)) {
+                Some(ex) => format!("#{}", ex.name),
+                None => line.to_string(),
+            }
+        })
+        .collect()
+}
+
+fn log_hits(jday: &JDay, re: &Regex, context: usize) -> Vec<String> {
+    let lines = log_lines(jday);
+    let mut shown = vec![false; lines.len()];
+    for (i, line) in lines.iter().enumerate() {
+        if re.is_match(line) {
+            let end = (i + context + 1).min(lines.len());
+            for flag in &mut shown[i.saturating_sub(context)..end] {
+                *flag = true;
+            }
+        }
+    }
+    let mut out = Vec::new();
+    let mut last: Option<usize> = None;
+    for (i, line) in lines.iter().enumerate().filter(|(i, _)| shown[*i]) {
+        if context > 0 && last.is_some_and(|l| l + 1 != i) {
+            out.push("  --".to_string());
+        }
+        out.push(format!("  {}", highlight(line, re)));
+        last = Some(i);
+    }
+    out
+}
+
+// "#squat  100 x 5 knee pain", with only the comment highlighted
+fn comment_hits(jday: &JDay, re: &Regex) -> Vec<String> {
+    let mut out = Vec::new();
+    for eblock in &jday.eblocks {
+        let name = jday.exercise(&eblock.eid).map(|ex| ex.name.as_str()).unwrap_or("");
+        for set in &eblock.sets {
+            if let Some(c) = &set.c
+                && re.is_match(c) {
+                let bare = Set { c: None, ..set.clone() };
+                out.push(format!(
+                    "  #{}  {} {}",
+                    formatters::color_exercise(name),
+                    formatters::format_set(&bare),
+                    highlight(c, re)
+                ));
+            }
+        }
+    }
+    out
+}
+
+// `days` are (YYYY-MM-DD, workout) pairs, oldest first
+pub fn search(days: &[(String, JDay)], re: &Regex, context: usize) -> Vec<DayHits> {
+    days.iter()
+        .filter_map(|(date, jday)| {
+            let mut lines = log_hits(jday, re, context);
+            lines.extend(comment_hits(jday, re));
+            if lines.is_empty() {
+                None
+            } else {
+                Some(DayHits { date: date.clone(), lines })
+            }
+        })
+        .collect()
+}
+
+pub fn render_hits(hits: &[DayHits]) -> String {
+    hits.iter()
+        .map(|h| format!("{}\n{}", formatters::color_date(&h.date), h.lines.join("\n")))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
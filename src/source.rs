@@ -0,0 +1,174 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use async_trait::async_trait;
+use chrono::NaiveDate;
+
+use crate::api::ApiClient;
+use crate::models::{JDay, User};
+use crate::parser::{self, Unit};
+use crate::workouts;
+
+// Where workouts come from: the weightxreps API, or a directory of
+// plain-text logs. Dates are YYYY-MM-DD, weights in kg either way.
+#[async_trait]
+pub trait WorkoutSource: Send + Sync {
+    // up to `count` most recent workout dates (all of them for 0) between
+    // oldest and latest inclusive, oldest first
+    async fn dates(&self, latest: Option<String>, oldest: Option<String>, count: u32) -> Result<Vec<String>, String>;
+
+    async fn workout(&self, date: &str) -> Result<JDay, String>;
+
+    // whose unit preference the output follows
+    async fn user(&self) -> Result<User, String>;
+
+    async fn dates_in_range(&self, oldest: NaiveDate, latest: NaiveDate) -> Result<Vec<String>, String> {
+        let count = ((latest - oldest).num_days().abs() + 1) as u32;
+        self.dates(Some(latest.to_string()), Some(oldest.to_string()), count).await
+    }
+
+    // many days at once, in the order of `dates`
+    async fn workouts(&self, dates: &[String]) -> Vec<(String, Result<JDay, String>)> {
+        let mut results = Vec::with_capacity(dates.len());
+        for date in dates {
+            results.push((date.clone(), self.workout(date).await));
+        }
+        results
+    }
+
+    // bodyweight logged between oldest and latest, oldest first, in kg
+    async fn bodyweights(&self, oldest: NaiveDate, latest: NaiveDate) -> Result<Vec<(String, f32)>, String> {
+        let dates = self.dates_in_range(oldest, latest).await?;
+        Ok(self.workouts(&dates).await
+            .into_iter()
+            .filter_map(|(date, result)| result.ok().and_then(|j| j.bw).filter(|&w| w > 0.0).map(|w| (date, w)))
+            .collect())
+    }
+}
+
+// the account logged in with the credentials file
+pub struct ApiSource<C> {
+    client: C,
+    token: String,
+}
+
+impl<C: ApiClient + Clone + 'static> ApiSource<C> {
+    pub fn new(client: C, token: String) -> Self {
+        ApiSource { client, token }
+    }
+}
+
+#[async_trait]
+impl<C: ApiClient + Clone + 'static> WorkoutSource for ApiSource<C> {
+    async fn dates(&self, latest: Option<String>, oldest: Option<String>, count: u32) -> Result<Vec<String>, String> {
+        workouts::get_dates(&self.client, &self.token, latest, oldest, count, false).await
+    }
+
+    async fn workout(&self, date: &str) -> Result<JDay, String> {
+        workouts::get_jday(&self.client, &self.token, date).await
+    }
+
+    async fn user(&self) -> Result<User, String> {
+        self.client.get_user_info(&self.token).await.map_err(|e| e.to_string())
+    }
+
+    async fn dates_in_range(&self, oldest: NaiveDate, latest: NaiveDate) -> Result<Vec<String>, String> {
+        workouts::get_dates_in_range(&self.client, &self.token, oldest, latest).await
+    }
+
+    async fn workouts(&self, dates: &[String]) -> Vec<(String, Result<JDay, String>)> {
+        workouts::get_jdays(&self.client, &self.token, dates).await
+    }
+
+    async fn bodyweights(&self, oldest: NaiveDate, latest: NaiveDate) -> Result<Vec<(String, f32)>, String> {
+        workouts::get_bodyweights(&self.client, &self.token, oldest, latest).await
+    }
+}
+
+// A directory of logs as `show` prints them, one workout per file, searched
+// recursively. A file's date comes from its name (2025-10-27, 2025-10-27.txt)
+// or else from its first line; files with neither are ignored, and of two
+// files with the same date the first path in sorted order is kept.
+pub struct DirSource {
+    files: BTreeMap<String, PathBuf>,
+    unit: Unit,
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), String> {
+    let entries = fs::read_dir(dir).map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+    for entry in entries {
+        let path = entry.map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?.path();
+        if path.file_name().and_then(|n| n.to_str()).is_some_and(|n| n.starts_with('.')) {
+            continue;
+        }
+        if path.is_dir() {
+            collect_files(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn file_date(path: &Path) -> Option<String> {
+    let name = path.file_name()?.to_str()?;
+    let from_name = name.get(0..10).and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok());
+    let date = match from_name {
+        Some(date) => date,
+        None => {
+            // only the first non-empty line is read, whatever the file's size
+            let file = fs::File::open(path).ok()?;
+            let first = BufReader::new(file).lines().map_while(Result::ok).find(|l| !l.trim().is_empty())?;
+            NaiveDate::parse_from_str(first.trim(), "%Y-%m-%d").ok()?
+        }
+    };
+    Some(date.to_string())
+}
+
+impl DirSource {
+    pub fn open(dir: &str, unit: Unit) -> Result<Self, String> {
+        let mut paths = Vec::new();
+        collect_files(Path::new(dir), &mut paths)?;
+        paths.sort();
+        let mut files: BTreeMap<String, PathBuf> = BTreeMap::new();
+        for path in paths {
+            let Some(date) = file_date(&path) else {
+                continue;
+            };
+            if let Some(other) = files.get(&date) {
+                eprintln!("Skipping {}: {} is already the log for {}", path.display(), other.display(), date);
+                continue;
+            }
+            files.insert(date, path);
+        }
+        Ok(DirSource { files, unit })
+    }
+}
+
+#[async_trait]
+impl WorkoutSource for DirSource {
+    async fn dates(&self, latest: Option<String>, oldest: Option<String>, count: u32) -> Result<Vec<String>, String> {
+        let mut dates: Vec<String> = self.files.keys()
+            .filter(|d| oldest.as_ref().is_none_or(|o| *d >= o))
+            .filter(|d| latest.as_ref().is_none_or(|l| *d <= l))
+            .cloned()
+            .collect();
+        if count > 0 && dates.len() > count as usize {
+            dates.drain(..dates.len() - count as usize);
+        }
+        Ok(dates)
+    }
+
+    async fn workout(&self, date: &str) -> Result<JDay, String> {
+        let path = self.files.get(date).ok_or("No workout found for the date.")?;
+        let text = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        parser::parse(&text, self.unit)
+            .map(|w| w.jday)
+            .map_err(|e| format!("{}:{}", path.display(), e))
+    }
+
+    async fn user(&self) -> Result<User, String> {
+        Ok(User { usekg: Some(if self.unit == Unit::Lb { 0 } else { 1 }) })
+    }
+}
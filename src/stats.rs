@@ -0,0 +1,133 @@
+use std::collections::BTreeMap;
+use chrono::{Datelike, NaiveDate};
+
+use crate::formatters;
+use crate::metrics;
+use crate::models::{JDay, Set};
+
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GroupBy {
+    #[default]
+    Week,
+    Month,
+    Exercise,
+}
+
+// weights are kept in kg, conversion happens when rendering
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Totals {
+    pub sessions: u32,
+    pub hard_sets: u32,
+    pub reps: u32,
+    pub tonnage: f32,
+    pub top_e1rm: f32,
+}
+
+impl Totals {
+    pub fn add(&mut self, sets: &[Set]) {
+        self.hard_sets += metrics::working_sets(sets);
+        self.reps += metrics::total_reps(sets);
+        self.tonnage += metrics::total_volume(sets);
+        self.top_e1rm = self.top_e1rm.max(metrics::best_e1rm(sets));
+    }
+}
+
+pub struct PeriodStats {
+    pub period: String,
+    pub exercises: BTreeMap<String, Totals>,
+    pub total: Totals,
+}
+
+// weeks are labelled by their Monday
+pub fn period_label(date: NaiveDate, by: GroupBy) -> String {
+    match by {
+        GroupBy::Week => {
+            let monday = date - chrono::Duration::days(date.weekday().num_days_from_monday() as i64);
+            monday.to_string()
+        }
+        GroupBy::Month => format!("{:04}-{:02}", date.year(), date.month()),
+        GroupBy::Exercise => String::new(),
+    }
+}
+
+// per exercise totals of a single workout
+pub fn day_totals(jday: &JDay) -> BTreeMap<String, Totals> {
+    let mut exercises: BTreeMap<String, Totals> = BTreeMap::new();
+    for eblock in &jday.eblocks {
+        if let Some(ex) = jday.exercise(&eblock.eid) {
+            exercises.entry(ex.name.clone()).or_default().add(&eblock.sets);
+        }
+    }
+    for totals in exercises.values_mut() {
+        totals.sessions = 1;
+    }
+    exercises
+}
+
+// `days` are (YYYY-MM-DD, workout) pairs, oldest first
+pub fn aggregate(days: &[(String, JDay)], by: GroupBy) -> Vec<PeriodStats> {
+    let mut periods: Vec<PeriodStats> = Vec::new();
+    for (date, jday) in days {
+        let Ok(day) = NaiveDate::parse_from_str(date, "%Y-%m-%d") else {
+            continue;
+        };
+        let label = period_label(day, by);
+        if periods.last().is_none_or(|p| p.period != label) {
+            periods.push(PeriodStats { period: label, exercises: BTreeMap::new(), total: Totals::default() });
+        }
+        let period = periods.last_mut().unwrap();
+        period.total.sessions += 1;
+        for (name, totals) in day_totals(jday) {
+            let entry = period.exercises.entry(name).or_default();
+            entry.sessions += totals.sessions;
+            entry.hard_sets += totals.hard_sets;
+            entry.reps += totals.reps;
+            entry.tonnage += totals.tonnage;
+            entry.top_e1rm = entry.top_e1rm.max(totals.top_e1rm);
+            period.total.hard_sets += totals.hard_sets;
+            period.total.reps += totals.reps;
+            period.total.tonnage += totals.tonnage;
+        }
+    }
+    periods
+}
+
+fn totals_cells(totals: &Totals, lb: bool) -> Vec<String> {
+    let e1rm = if totals.top_e1rm > 0.0 {
+        formatters::format_weight(totals.top_e1rm, lb)
+    } else {
+        String::new()
+    };
+    vec![
+        totals.sessions.to_string(),
+        totals.hard_sets.to_string(),
+        totals.reps.to_string(),
+        formatters::format_weight(totals.tonnage, lb),
+        e1rm,
+    ]
+}
+
+pub fn render_stats(periods: &[PeriodStats], by: GroupBy, lb: bool) -> String {
+    let mut headers = vec!["exercise", "sessions", "hard sets", "reps", "tonnage", "top e1RM"];
+    if by != GroupBy::Exercise {
+        headers.insert(0, if by == GroupBy::Week { "week" } else { "month" });
+    }
+    let mut rows = Vec::new();
+    for period in periods {
+        for (i, (name, totals)) in period.exercises.iter().enumerate() {
+            let mut row = vec![formatters::color_exercise(name)];
+            if by != GroupBy::Exercise {
+                row.insert(0, if i == 0 { period.period.clone() } else { String::new() });
+            }
+            row.extend(totals_cells(totals, lb));
+            rows.push(row);
+        }
+        let mut row = vec!["total".to_string()];
+        if by != GroupBy::Exercise {
+            row.insert(0, String::new());
+        }
+        row.extend(totals_cells(&period.total, lb));
+        rows.push(row);
+    }
+    formatters::render_table(&headers, &rows)
+}
@@ -0,0 +1,158 @@
+use chrono::{Datelike, NaiveDate};
+
+use crate::formatters;
+
+// a run of consecutive training days or weeks; weeks are identified by Monday
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Streak {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub len: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Gap {
+    // last session before the gap and the first one after it
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub days: i64,
+}
+
+#[derive(Debug)]
+pub struct Adherence {
+    pub target: f32,
+    pub weeks_on_target: u32,
+    pub weeks: u32,
+    // sessions over the last four complete weeks against 4 x target
+    pub recent_sessions: u32,
+}
+
+#[derive(Debug)]
+pub struct StreakReport {
+    pub sessions: u32,
+    pub current_days: Option<Streak>,
+    pub longest_days: Option<Streak>,
+    pub current_weeks: Option<Streak>,
+    pub longest_weeks: Option<Streak>,
+    pub sessions_per_week: f32,
+    pub gaps: Vec<Gap>,
+    pub adherence: Option<Adherence>,
+}
+
+// number of longest gaps reported
+const GAPS: usize = 3;
+
+pub fn monday(date: NaiveDate) -> NaiveDate {
+    date - chrono::Duration::days(date.weekday().num_days_from_monday() as i64)
+}
+
+// runs of `points` (sorted, distinct) that are `step` days apart
+fn runs(points: &[NaiveDate], step: i64) -> Vec<Streak> {
+    let mut runs: Vec<Streak> = Vec::new();
+    for &point in points {
+        match runs.last_mut() {
+            Some(run) if (point - run.end).num_days() == step => {
+                run.end = point;
+                run.len += 1;
+            }
+            _ => runs.push(Streak { start: point, end: point, len: 1 }),
+        }
+    }
+    runs
+}
+
+// the earliest of equally long runs wins
+fn longest(runs: &[Streak]) -> Option<Streak> {
+    runs.iter().copied().reduce(|best, r| if r.len > best.len { r } else { best })
+}
+
+// a streak is still alive if it reaches the current period or the one before,
+// since today's (or this week's) session may not have happened yet
+fn current(runs: &[Streak], now: NaiveDate, step: i64) -> Option<Streak> {
+    runs.last().copied().filter(|r| (now - r.end).num_days() <= step)
+}
+
+// `dates` in any order; `today` anchors the current streaks and the weeks
+// counted for averages and adherence
+pub fn build_report(dates: &[NaiveDate], today: NaiveDate, target: Option<f32>) -> StreakReport {
+    let mut days = dates.to_vec();
+    days.sort();
+    days.dedup();
+    let mut weeks: Vec<NaiveDate> = days.iter().map(|&d| monday(d)).collect();
+    weeks.dedup();
+
+    let day_runs = runs(&days, 1);
+    let week_runs = runs(&weeks, 7);
+    let this_week = monday(today);
+
+    let span_weeks = days.first()
+        .map(|&first| ((this_week - monday(first)).num_days() / 7 + 1) as u32)
+        .unwrap_or(0);
+
+    let mut gaps: Vec<Gap> = days.windows(2)
+        .map(|w| Gap { from: w[0], to: w[1], days: (w[1] - w[0]).num_days() - 1 })
+        .filter(|g| g.days > 0)
+        .collect();
+    gaps.sort_by(|a, b| b.days.cmp(&a.days).then(b.to.cmp(&a.to)));
+    gaps.truncate(GAPS);
+
+    let adherence = target.filter(|_| !days.is_empty()).map(|target| {
+        // the current week is still in progress, so only complete weeks count
+        let complete = span_weeks.saturating_sub(1);
+        let first_week = this_week - chrono::Duration::weeks(complete as i64);
+        let per_week = |week: NaiveDate| days.iter().filter(|&&d| monday(d) == week).count() as f32;
+        let weeks_on_target = (0..complete)
+            .map(|i| first_week + chrono::Duration::weeks(i as i64))
+            .filter(|&w| per_week(w) >= target)
+            .count() as u32;
+        let recent_start = this_week - chrono::Duration::weeks(4);
+        let recent_sessions = days.iter().filter(|&&d| d >= recent_start && d < this_week).count() as u32;
+        Adherence { target, weeks_on_target, weeks: complete, recent_sessions }
+    });
+
+    StreakReport {
+        sessions: days.len() as u32,
+        current_days: current(&day_runs, today, 1),
+        longest_days: longest(&day_runs),
+        current_weeks: current(&week_runs, this_week, 7),
+        longest_weeks: longest(&week_runs),
+        sessions_per_week: if span_weeks > 0 { days.len() as f32 / span_weeks as f32 } else { 0.0 },
+        gaps,
+        adherence,
+    }
+}
+
+fn streak_cell(streak: Option<Streak>, unit: &str) -> String {
+    match streak {
+        Some(s) if s.len > 1 => format!("{} ({} → {})", formatters::plural(s.len, unit), formatters::color_date(&s.start.to_string()), formatters::color_date(&s.end.to_string())),
+        Some(s) => format!("{} ({})", formatters::plural(s.len, unit), formatters::color_date(&s.start.to_string())),
+        None => format!("0 {}s", unit),
+    }
+}
+
+fn percent(n: f32, of: f32) -> String {
+    if of > 0.0 { format!("{:.0}%", n / of * 100.0) } else { "-".to_string() }
+}
+
+pub fn render_report(report: &StreakReport) -> String {
+    let mut rows = vec![
+        ("sessions", report.sessions.to_string()),
+        ("current streak", format!("{}, {}", streak_cell(report.current_weeks, "week"), streak_cell(report.current_days, "day"))),
+        ("longest streak", format!("{}, {}", streak_cell(report.longest_weeks, "week"), streak_cell(report.longest_days, "day"))),
+        ("per week", format!("{:.1} sessions", report.sessions_per_week)),
+    ];
+    for (i, gap) in report.gaps.iter().enumerate() {
+        let label = if i == 0 { "longest gaps" } else { "" };
+        rows.push((label, format!("{} ({} → {})", formatters::plural(gap.days as u32, "day"), formatters::color_date(&gap.from.to_string()), formatters::color_date(&gap.to.to_string()))));
+    }
+    if let Some(a) = &report.adherence {
+        rows.push(("target", format!("{} sessions/week", formatters::format_number(a.target))));
+        rows.push(("weeks on target", format!("{}/{} ({})", a.weeks_on_target, a.weeks, percent(a.weeks_on_target as f32, a.weeks as f32))));
+        rows.push(("last 4 weeks", format!("{}/{} sessions ({})", a.recent_sessions, formatters::format_number(a.target * 4.0), percent(a.recent_sessions as f32, a.target * 4.0))));
+    }
+    let width = rows.iter().map(|(label, _)| label.len()).max().unwrap_or(0);
+    rows.iter()
+        .map(|(label, value)| format!("{:<width$}  {}", label, value))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,64 @@
+use crate::aliases::{self, Aliases};
+use crate::charts;
+use crate::formatters;
+use crate::metrics::{self, Formula};
+use crate::models::{JDay, Set};
+use crate::rpe::RpeTable;
+
+// the set with the best estimated 1RM of one session; weights in kg
+#[derive(Debug, Clone)]
+pub struct SessionBest {
+    pub date: String,
+    pub e1rm: f32,
+    pub set: Set,
+}
+
+// `days` are (YYYY-MM-DD, workout) pairs, oldest first. Blocks match as in
+// `exercise`: by name, by tag, and with `aliases` by parent lift. `table` is
+// the RPE chart the rpe formula reads.
+pub fn session_bests(days: &[(String, JDay)], exercise: &str, formula: Formula, table: &RpeTable, aliases: Option<&Aliases>) -> Vec<SessionBest> {
+    let mut bests = Vec::new();
+    for (date, jday) in days {
+        let best = jday.eblocks.iter()
+            .filter(|b| aliases::block_matches(jday, b, exercise, aliases))
+            .flat_map(|b| &b.sets)
+            .map(|s| (metrics::estimate_1rm(s, formula, table), s))
+            .filter(|(e1rm, _)| *e1rm > 0.0)
+            .max_by(|a, b| a.0.total_cmp(&b.0));
+        if let Some((e1rm, set)) = best {
+            bests.push(SessionBest { date: date.clone(), e1rm, set: set.clone() });
+        }
+    }
+    bests
+}
+
+pub fn render_trend(bests: &[SessionBest], lb: bool) -> String {
+    let rows: Vec<Vec<String>> = bests.iter()
+        .map(|b| vec![
+            formatters::color_date(&b.date),
+            formatters::format_set(&b.set),
+            formatters::format_weight(b.e1rm, lb),
+        ])
+        .collect();
+    let mut out = vec![formatters::render_table(&["date", "best set", "e1RM"], &rows)];
+    let values: Vec<f32> = bests.iter().map(|b| b.e1rm).collect();
+    if values.len() > 1 {
+        out.push(String::new());
+        out.push(charts::line_chart(&values, 8, |v| formatters::format_weight(v, lb)));
+        let first = values[0];
+        let last = values[values.len() - 1];
+        let scale = if lb { formatters::LB_PER_KG } else { 1.0 };
+        let delta = ((last - first) * scale).round();
+        let pct = (last - first) / first * 100.0;
+        out.push(String::new());
+        out.push(format!(
+            "{}  {} → {} ({}, {})",
+            charts::sparkline(&values),
+            formatters::format_weight(first, lb),
+            formatters::format_weight(last, lb),
+            formatters::color_delta(&formatters::format_delta(delta), delta),
+            formatters::color_delta(&format!("{}%", formatters::format_delta((pct * 10.0).round() / 10.0)), pct)
+        ));
+    }
+    out.join("\n")
+}
@@ -2,11 +2,13 @@ use crate::api;
 use crate::auth;
 use crate::formatters;
 use crate::models;
-use chrono::{Datelike, Utc};
+use chrono::{Datelike, NaiveDate, Utc};
+use std::sync::Arc;
+use tokio::sync::Semaphore;
 
 
 pub async fn get_jday<C: crate::api::ApiClient>(client: &C, token: &str, date: &str) -> Result<models::JDay, String> {
-    let claims = auth::decode_token(&token).map_err(|e| e.to_string())?;
+    let claims = auth::decode_token(token).map_err(|e| e.to_string())?;
     let uid = claims.id;
 
     let query = format!(r#"
@@ -43,19 +45,17 @@ query {{
 }
 
 pub async fn get_day<C: crate::api::ApiClient>(client: &C, token: &str, date: &str) -> Result<String, String> {
+    get_day_with(client, token, date, &formatters::FormatOptions::default()).await
+}
+
+pub async fn get_day_with<C: crate::api::ApiClient>(client: &C, token: &str, date: &str, opts: &formatters::FormatOptions) -> Result<String, String> {
     let jday = get_jday(client, token, date).await?;
     let user = client.get_user_info(token).await.map_err(|e| e.to_string())?;
-    let formatted = formatters::format_workout(&jday);
-    let mut bw = jday.bw.unwrap_or(0.0);
-    if user.usekg.unwrap_or(1) != 1 {
-        bw *= 2.20462; // convert kg to lb
-    }
-    let output = format!("{}\n@ {} bw\n{}", formatters::color_date(date), formatters::color_bw(&format!("{:.0}", bw)), formatted);
-    Ok(output)
+    Ok(formatters::render_workout_with(date, &jday, &user, opts))
 }
 
 pub async fn get_dates<C: crate::api::ApiClient>(client: &C, token: &str, latest: Option<String>, oldest: Option<String>, count: u32, reverse: bool) -> Result<Vec<String>, String> {
-    let claims = auth::decode_token(&token).map_err(|e| e.to_string())?;
+    let claims = auth::decode_token(token).map_err(|e| e.to_string())?;
     let uid = claims.id;
 
     let initial_ymd = latest.clone().unwrap_or_else(|| {
@@ -97,8 +97,8 @@ query GetJRange($uid: ID!, $ymd: YMD!, $range: Int!) {
         };
 
         let mut date_strings: Vec<String> = days.into_iter()
-            .filter_map(|day| day.on)
-            .map(|d| format!("{}-{}-{}", &d[0..4], &d[5..7], &d[8..10]))
+            // skip days whose date is too short to be one
+            .filter_map(|day| Some(day.on?.get(..10)?.to_string()))
             .collect();
 
         if date_strings.is_empty() {
@@ -108,21 +108,22 @@ query GetJRange($uid: ID!, $ymd: YMD!, $range: Int!) {
         date_strings.sort();
 
         // Filter dates
-        let filtered: Vec<String> = date_strings.iter().cloned()
+        let filtered: Vec<String> = date_strings.iter()
             .filter(|d| {
                 if let Some(old) = &oldest {
-                    d >= old
+                    *d >= old
                 } else {
                     true
                 }
             })
             .filter(|d| {
                 if let Some(lat) = &latest {
-                    d <= lat
+                    *d <= lat
                 } else {
                     true
                 }
             })
+            .cloned()
             .collect();
 
         all_dates.extend(filtered);
@@ -133,12 +134,10 @@ query GetJRange($uid: ID!, $ymd: YMD!, $range: Int!) {
         }
 
         // Check if we reached the oldest
-        if let Some(old) = &oldest {
-            if let Some(batch_oldest) = date_strings.first() {
-                if batch_oldest < old {
-                    break;
-                }
-            }
+        if let Some(old) = &oldest
+            && let Some(batch_oldest) = date_strings.first()
+            && batch_oldest < old {
+            break;
         }
 
         // Set next ymd to the oldest in this batch to get older dates
@@ -166,3 +165,99 @@ query GetJRange($uid: ID!, $ymd: YMD!, $range: Int!) {
     }
     Ok(result)
 }
+
+// every workout date between oldest and latest, inclusive, oldest first
+pub async fn get_dates_in_range<C: crate::api::ApiClient>(client: &C, token: &str, oldest: NaiveDate, latest: NaiveDate) -> Result<Vec<String>, String> {
+    let count = ((latest - oldest).num_days().abs() + 1) as u32;
+    get_dates(client, token, Some(latest.to_string()), Some(oldest.to_string()), count, false).await
+}
+
+// how many workouts get_jdays fetches at the same time
+const MAX_CONCURRENT_FETCHES: usize = 8;
+
+// fetch many days at once, a few at a time; results come back in the order
+// of `dates`
+pub async fn get_jdays<C: crate::api::ApiClient + Clone + 'static>(client: &C, token: &str, dates: &[String]) -> Vec<(String, Result<models::JDay, String>)> {
+    let permits = Arc::new(Semaphore::new(MAX_CONCURRENT_FETCHES));
+    let tasks: Vec<_> = dates.iter()
+        .map(|date| {
+            let client = client.clone();
+            let token = token.to_string();
+            let date = date.clone();
+            let permits = permits.clone();
+            tokio::spawn(async move {
+                let _permit = permits.acquire().await;
+                get_jday(&client, &token, &date).await
+            })
+        })
+        .collect();
+    let mut results = Vec::with_capacity(dates.len());
+    for (date, task) in dates.iter().zip(tasks) {
+        let result = task.await.unwrap_or_else(|e| Err(format!("fetching {} failed: {}", date, e)));
+        results.push((date.clone(), result));
+    }
+    results
+}
+
+// bodyweight logged between oldest and latest, oldest first, in kg; read from
+// jrange so no per-day fetch is needed, falling back to jday only when the
+// server does not offer bw there
+pub async fn get_bodyweights<C: crate::api::ApiClient + Clone + 'static>(client: &C, token: &str, oldest: NaiveDate, latest: NaiveDate) -> Result<Vec<(String, f32)>, String> {
+    let claims = auth::decode_token(token).map_err(|e| e.to_string())?;
+    let uid = claims.id;
+
+    let query = r#"
+query GetJRangeBw($uid: ID!, $ymd: YMD!, $range: Int!) {
+  jrange(uid: $uid, ymd: $ymd, range: $range) {
+    days {
+      on
+      bw
+    }
+  }
+}
+"#;
+
+    let oldest_ymd = oldest.to_string();
+    let mut current_ymd = latest.to_string();
+    let mut weights: Vec<(String, f32)> = Vec::new();
+    loop {
+        let variables = serde_json::json!({ "uid": uid.to_string(), "ymd": current_ymd.clone(), "range": 32 });
+        let response: models::GraphQLResponse<models::GetJRangeBwData> = api::graphql_request(client, token, query, Some(variables)).await.map_err(|e| e.to_string())?;
+        if let Some(errors) = response.errors {
+            if errors.iter().any(|e| e.message.contains("Cannot query field \"bw\"")) {
+                return get_bodyweights_from_jdays(client, token, oldest, latest).await;
+            }
+            return Err(errors.into_iter().map(|e| e.message).collect::<Vec<_>>().join("; "));
+        }
+        let days = response.data
+            .and_then(|d| d.jrange)
+            .and_then(|r| r.days)
+            .unwrap_or_default();
+        let mut batch: Vec<(String, Option<f32>)> = days.into_iter()
+            // skip days whose date is too short to be one
+            .filter_map(|day| Some((day.on?.get(..10)?.to_string(), day.bw)))
+            .collect();
+        batch.sort_by(|a, b| a.0.cmp(&b.0));
+        let Some(batch_oldest) = batch.first().map(|(d, _)| d.clone()) else {
+            break;
+        };
+        weights.extend(batch.into_iter()
+            .filter(|(d, _)| *d >= oldest_ymd && *d <= current_ymd)
+            .filter_map(|(d, bw)| bw.filter(|&w| w > 0.0).map(|w| (d, w))));
+        if batch_oldest <= oldest_ymd || batch_oldest == current_ymd {
+            break;
+        }
+        current_ymd = batch_oldest;
+    }
+    weights.sort_by(|a, b| a.0.cmp(&b.0));
+    weights.dedup_by(|a, b| a.0 == b.0);
+    Ok(weights)
+}
+
+async fn get_bodyweights_from_jdays<C: crate::api::ApiClient + Clone + 'static>(client: &C, token: &str, oldest: NaiveDate, latest: NaiveDate) -> Result<Vec<(String, f32)>, String> {
+    let dates = get_dates_in_range(client, token, oldest, latest).await?;
+    Ok(get_jdays(client, token, &dates).await
+        .into_iter()
+        .filter_map(|(date, result)| result.ok().and_then(|j| j.bw).filter(|&w| w > 0.0).map(|w| (date, w)))
+        .collect())
+}
@@ -0,0 +1,159 @@
+use crate::formatters;
+use crate::metrics;
+use crate::models::{JDay, Set};
+
+// what a set's intensity is measured against
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Reference {
+    // best estimated 1RM in the range
+    #[default]
+    E1rm,
+    // training max from the config file
+    TrainingMax,
+    // the intensity the server reports per set
+    Server,
+}
+
+// Prilepin's guidelines for one zone, per lift per session
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Prilepin {
+    pub reps_per_set: (u32, u32),
+    pub optimal: u32,
+    pub total: (u32, u32),
+}
+
+pub struct Zone {
+    pub label: &'static str,
+    // percent of the reference, lower bound inclusive
+    pub from: f32,
+    pub prilepin: Option<Prilepin>,
+}
+
+pub const ZONES: [Zone; 5] = [
+    Zone { label: "<55%", from: 0.0, prilepin: None },
+    Zone { label: "55-69%", from: 55.0, prilepin: Some(Prilepin { reps_per_set: (3, 6), optimal: 24, total: (18, 30) }) },
+    Zone { label: "70-79%", from: 70.0, prilepin: Some(Prilepin { reps_per_set: (3, 6), optimal: 18, total: (12, 24) }) },
+    Zone { label: "80-89%", from: 80.0, prilepin: Some(Prilepin { reps_per_set: (2, 4), optimal: 15, total: (10, 20) }) },
+    Zone { label: "90%+", from: 90.0, prilepin: Some(Prilepin { reps_per_set: (1, 2), optimal: 7, total: (4, 10) }) },
+];
+
+pub fn zone_index(pct: f32) -> usize {
+    ZONES.iter().rposition(|z| pct >= z.from).unwrap_or(0)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionZones {
+    pub date: String,
+    pub reps: [u32; ZONES.len()],
+    pub sets: [u32; ZONES.len()],
+    // sets with more or fewer reps than Prilepin suggests for their zone
+    pub off_sets: [u32; ZONES.len()],
+}
+
+// best e1RM of the exercise over `days`, the default reference
+pub fn best_e1rm(days: &[(String, JDay)], exercise: &str) -> Option<(f32, String)> {
+    days.iter()
+        .flat_map(|(date, jday)| {
+            jday.eblocks.iter()
+                .filter(|b| jday.eblock_matches(b, exercise))
+                .flat_map(|b| &b.sets)
+                .map(move |s| (metrics::set_e1rm(s), date.clone()))
+        })
+        .filter(|(e1rm, _)| *e1rm > 0.0)
+        .max_by(|a, b| a.0.total_cmp(&b.0))
+}
+
+// Working sets of the exercise bucketed per session. `percent` gives a set's
+// intensity against the reference; sets it cannot place are left out.
+pub fn session_zones(days: &[(String, JDay)], exercise: &str, percent: impl Fn(&Set) -> Option<f32>) -> Vec<SessionZones> {
+    let mut sessions = Vec::new();
+    for (date, jday) in days {
+        let mut session = SessionZones {
+            date: date.clone(),
+            reps: [0; ZONES.len()],
+            sets: [0; ZONES.len()],
+            off_sets: [0; ZONES.len()],
+        };
+        for eblock in jday.eblocks.iter().filter(|b| jday.eblock_matches(b, exercise)) {
+            for set in metrics::working(&eblock.sets).filter(|s| s.is_weight_reps() && !s.is_bodyweight()) {
+                let Some(pct) = percent(set) else {
+                    continue;
+                };
+                let zone = zone_index(pct);
+                let (r, n) = (set.r.unwrap_or(0), set.s.unwrap_or(1).max(1));
+                session.reps[zone] += r * n;
+                session.sets[zone] += n;
+                if let Some(p) = ZONES[zone].prilepin
+                    && (r < p.reps_per_set.0 || r > p.reps_per_set.1) {
+                    session.off_sets[zone] += n;
+                }
+            }
+        }
+        if session.sets.iter().any(|&n| n > 0) {
+            sessions.push(session);
+        }
+    }
+    sessions
+}
+
+// where a session strays from Prilepin's chart, e.g. "80-89% 24 > 20"
+pub fn check(session: &SessionZones) -> Vec<String> {
+    let mut issues = Vec::new();
+    for (i, zone) in ZONES.iter().enumerate() {
+        let Some(p) = zone.prilepin else {
+            continue;
+        };
+        let reps = session.reps[i];
+        if reps == 0 {
+            continue;
+        }
+        if reps < p.total.0 {
+            issues.push(format!("{} {} < {}", zone.label, reps, p.total.0));
+        } else if reps > p.total.1 {
+            issues.push(format!("{} {} > {}", zone.label, reps, p.total.1));
+        }
+        if session.off_sets[i] > 0 {
+            issues.push(format!("{} {} outside {}-{} reps", zone.label, formatters::plural(session.off_sets[i], "set"), p.reps_per_set.0, p.reps_per_set.1));
+        }
+    }
+    issues
+}
+
+pub fn render_zones(sessions: &[SessionZones], reference: &str) -> String {
+    let mut out = vec![format!("reference  {}", reference), String::new()];
+
+    let total_reps: u32 = sessions.iter().flat_map(|s| s.reps).sum();
+    let rows: Vec<Vec<String>> = ZONES.iter().enumerate()
+        .map(|(i, zone)| {
+            let reps: u32 = sessions.iter().map(|s| s.reps[i]).sum();
+            let sets: u32 = sessions.iter().map(|s| s.sets[i]).sum();
+            let share = if total_reps > 0 { reps as f32 / total_reps as f32 * 100.0 } else { 0.0 };
+            let guide = zone.prilepin
+                .map(|p| format!("{}-{} reps/set, {} ({}-{}) per session", p.reps_per_set.0, p.reps_per_set.1, p.optimal, p.total.0, p.total.1))
+                .unwrap_or_default();
+            vec![zone.label.to_string(), sets.to_string(), reps.to_string(), format!("{:.0}%", share), guide]
+        })
+        .collect();
+    out.push(formatters::render_table(&["zone", "sets", "reps", "share", "Prilepin"], &rows));
+
+    let mut headers = vec!["date"];
+    headers.extend(ZONES.iter().map(|z| z.label));
+    headers.push("Prilepin");
+    let rows: Vec<Vec<String>> = sessions.iter()
+        .map(|s| {
+            let mut row = vec![formatters::color_date(&s.date)];
+            row.extend(s.reps.iter().map(|&r| if r > 0 { r.to_string() } else { String::new() }));
+            let issues = check(s);
+            row.push(if issues.is_empty() { "✓".to_string() } else { formatters::color_delta(&issues.join(", "), -1.0) });
+            row
+        })
+        .collect();
+    out.push(String::new());
+    out.push(formatters::render_table(&headers, &rows));
+    out.join("\n")
+}
+
+// "e1RM 180 (2025-10-03)"
+pub fn describe_e1rm(e1rm: f32, date: &str, lb: bool) -> String {
+    format!("e1RM {} ({})", formatters::format_weight(e1rm, lb), formatters::color_date(date))
+}
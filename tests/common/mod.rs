@@ -0,0 +1,63 @@
+// Fixtures shared by the integration tests; each test crate uses only some
+#![allow(dead_code)]
+
+use chrono::NaiveDate;
+use wxrust::formatters;
+use wxrust::models::{EBlock, Exercise, ExerciseWrapper, JDay, Set};
+
+// render without escape codes, whatever the terminal
+pub fn no_color() {
+    formatters::set_color("never");
+}
+
+// YYYY-MM-DD
+pub fn date(s: &str) -> NaiveDate {
+    s.parse().unwrap()
+}
+
+// w x r, in kg
+pub fn set(w: f32, r: u32) -> Set {
+    Set { w: Some(w), r: Some(r), lb: Some(0.0), ..Default::default() }
+}
+
+// w x r x s
+pub fn set_x(w: f32, r: u32, s: u32) -> Set {
+    Set { s: Some(s), ..set(w, r) }
+}
+
+pub fn set_rpe(w: f32, r: u32, rpe: Option<f32>) -> Set {
+    Set { rpe, ..set(w, r) }
+}
+
+pub fn set_comment(w: f32, r: u32, c: &str) -> Set {
+    Set { c: Some(c.to_string()), ..set(w, r) }
+}
+
+// a workout with a log and (id, exercise name, sets) blocks
+pub fn workout(log: &str, blocks: &[(&str, &str, Vec<Set>)]) -> JDay {
+    JDay {
+        log: log.to_string(),
+        bw: None,
+        eblocks: blocks.iter().map(|(id, _, sets)| EBlock { eid: id.to_string(), sets: sets.clone() }).collect(),
+        exercises: blocks.iter()
+            .map(|(id, name, _)| ExerciseWrapper { exercise: Exercise { id: id.to_string(), name: name.to_string(), ex_type: None } })
+            .collect(),
+    }
+}
+
+// a workout with a log and (exercise name, sets) blocks, numbered from 1
+pub fn logged_day(log: &str, blocks: &[(&str, Vec<Set>)]) -> JDay {
+    let ids: Vec<String> = (1..=blocks.len()).map(|i| i.to_string()).collect();
+    let blocks: Vec<_> = ids.iter().zip(blocks).map(|(id, (name, sets))| (id.as_str(), *name, sets.clone())).collect();
+    workout(log, &blocks)
+}
+
+// a workout of one exercise
+pub fn day(name: &str, sets: Vec<Set>) -> JDay {
+    logged_day("", &[(name, sets)])
+}
+
+// a workout of one exercise with a logged bodyweight
+pub fn day_bw(bw: Option<f32>, name: &str, sets: Vec<Set>) -> JDay {
+    JDay { bw, ..day(name, sets) }
+}
@@ -0,0 +1,84 @@
+mod common;
+
+use std::collections::BTreeMap;
+use wxrust::aliases::{group_days, Aliases};
+use wxrust::history::exercise_history_with;
+use wxrust::models::JDay;
+use wxrust::records::build_records;
+use wxrust::stats::{aggregate, GroupBy};
+use common::{logged_day, set};
+
+fn variants() -> Vec<(String, JDay)> {
+    vec![
+        ("2025-08-29".to_string(), logged_day("EBLOCK:1\nEBLOCK:2\n", &[("safety-squat #sq", vec![set(100.0, 5)]), ("bench-press #bp", vec![set(100.0, 5)])])),
+        ("2025-09-05".to_string(), logged_day("EBLOCK:1 #safety-box-squat #sq\n", &[("safety box squat", vec![set(100.0, 5)])])),
+        ("2025-09-12".to_string(), logged_day("EBLOCK:1\n", &[("pendulum squat", vec![set(100.0, 5)])])),
+    ]
+}
+
+#[test]
+fn test_resolve_by_secondary_tag() {
+    let aliases = Aliases::default();
+    let days = variants();
+    assert_eq!(aliases.resolve(&days[0].1, &days[0].1.eblocks[0]).unwrap(), "sq");
+    assert_eq!(aliases.resolve(&days[1].1, &days[1].1.eblocks[0]).unwrap(), "sq");
+    assert_eq!(aliases.resolve(&days[2].1, &days[2].1.eblocks[0]).unwrap(), "pendulum squat");
+}
+
+#[test]
+fn test_resolve_by_config() {
+    let mut config = BTreeMap::new();
+    config.insert("sq".to_string(), "squat".to_string());
+    config.insert("Pendulum Squat".to_string(), "#sq".to_string());
+    let aliases = Aliases::new(&config);
+    let days = variants();
+    assert_eq!(aliases.resolve(&days[0].1, &days[0].1.eblocks[0]).unwrap(), "squat");
+    assert_eq!(aliases.resolve(&days[2].1, &days[2].1.eblocks[0]).unwrap(), "squat");
+
+    // a cycle stops instead of looping
+    let mut cycle = BTreeMap::new();
+    cycle.insert("a".to_string(), "b".to_string());
+    cycle.insert("b".to_string(), "a".to_string());
+    let jday = logged_day("EBLOCK:1\n", &[("a", vec![set(100.0, 5)])]);
+    assert!(Aliases::new(&cycle).resolve(&jday, &jday.eblocks[0]).is_some());
+}
+
+#[test]
+fn test_group_days() {
+    let grouped = group_days(&variants(), &Aliases::default());
+    let periods = aggregate(&grouped, GroupBy::Exercise);
+    let names: Vec<&String> = periods.iter().flat_map(|p| p.exercises.keys()).collect();
+    assert!(names.contains(&&"sq".to_string()));
+    assert!(names.contains(&&"bp".to_string()));
+    assert!(!names.contains(&&"safety-squat #sq".to_string()));
+    // the original days are left alone
+    assert_eq!(variants()[0].1.exercises[0].exercise.name, "safety-squat #sq");
+}
+
+#[test]
+fn test_history_grouping() {
+    let days = variants();
+    let grouped = exercise_history_with(&days, "sq", Some(&Aliases::default()));
+    assert_eq!(grouped.len(), 2);
+    assert_eq!(grouped[0].exercise, "safety box squat");
+
+    let mut config = BTreeMap::new();
+    config.insert("sq".to_string(), "squat".to_string());
+    let aliases = Aliases::new(&config);
+    assert_eq!(exercise_history_with(&days, "squat", Some(&aliases)).len(), 2);
+    assert_eq!(exercise_history_with(&days, "squat", None).len(), 0);
+}
+
+#[test]
+fn test_records_query_before_grouping() {
+    let days = variants();
+    let aliases = Aliases::default();
+    // a variant is found by its own name, and shown under its parent
+    let records = build_records(&days, Some("safety box squat"), Some(&aliases));
+    assert_eq!(records.keys().collect::<Vec<_>>(), vec!["sq"]);
+    assert_eq!(records["sq"].best_e1rm.as_ref().unwrap().date, "2025-09-05");
+    // the parent takes in every variant
+    let records = build_records(&days, Some("sq"), Some(&aliases));
+    assert_eq!(records["sq"].best_e1rm.as_ref().unwrap().date, "2025-08-29");
+    assert_eq!(build_records(&days, Some("bp"), Some(&aliases)).keys().collect::<Vec<_>>(), vec!["bp"]);
+}
@@ -0,0 +1,50 @@
+mod common;
+
+use wxrust::bodyweight::{moving_averages, render_bodyweight, weekly_rate};
+use common::date;
+
+#[test]
+fn test_moving_averages() {
+    let weights = vec![
+        (date("2025-10-01"), 80.0),
+        (date("2025-10-05"), 82.0),
+        (date("2025-10-09"), 84.0),
+    ];
+    let entries = moving_averages(&weights);
+    assert_eq!(entries[0].avg7, 80.0);
+    assert_eq!(entries[1].avg7, 81.0);
+    // 10-01 falls outside the 7 days ending 10-09
+    assert_eq!(entries[2].avg7, 83.0);
+    assert_eq!(entries[2].avg28, 82.0);
+}
+
+#[test]
+fn test_weekly_rate() {
+    let weights = vec![
+        (date("2025-10-01"), 80.0),
+        (date("2025-10-08"), 80.5),
+        (date("2025-10-15"), 81.0),
+    ];
+    assert!((weekly_rate(&weights).unwrap() - 0.5).abs() < 0.001);
+    assert_eq!(weekly_rate(&weights[..1]), None);
+    assert_eq!(weekly_rate(&[]), None);
+}
+
+#[test]
+fn test_render_bodyweight() {
+    common::no_color();
+    let weights = vec![
+        (date("2025-10-01"), 80.0),
+        (date("2025-10-08"), 81.0),
+    ];
+    let out = render_bodyweight(&weights);
+    let lines: Vec<&str> = out.lines().collect();
+    assert!(lines[0].starts_with("date"));
+    assert!(lines[0].contains("7d avg"));
+    assert!(lines[2].contains("80.0"));
+    assert!(out.contains("rate over range    +1/wk"));
+    assert!(out.contains("rate last 4 weeks  +1/wk"));
+
+    let single = render_bodyweight(&weights[..1]);
+    assert!(!single.contains("rate"));
+}
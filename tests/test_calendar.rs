@@ -0,0 +1,49 @@
+mod common;
+
+use std::collections::BTreeMap;
+use wxrust::calendar::{day_value, level, render_calendar, Metric};
+use wxrust::models::{EBlock, Exercise, ExerciseWrapper, JDay, Set};
+use common::date;
+
+#[test]
+fn test_level() {
+    assert_eq!(level(0.0, 100.0), 0);
+    assert_eq!(level(1.0, 100.0), 1);
+    assert_eq!(level(50.0, 100.0), 2);
+    assert_eq!(level(51.0, 100.0), 3);
+    assert_eq!(level(100.0, 100.0), 4);
+    assert_eq!(level(1.0, 1.0), 4);
+}
+
+#[test]
+fn test_day_value() {
+    let set = Set { w: Some(100.0), r: Some(5), s: Some(3), lb: Some(0.0), ..Default::default() };
+    let jday = JDay {
+        log: "".to_string(),
+        bw: None,
+        eblocks: vec![EBlock { eid: "1".to_string(), sets: vec![set] }],
+        exercises: vec![ExerciseWrapper {
+            exercise: Exercise { id: "1".to_string(), name: "squat".to_string(), ex_type: None },
+        }],
+    };
+    assert_eq!(day_value(&jday, Metric::Presence), 1.0);
+    assert_eq!(day_value(&jday, Metric::Tonnage), 1500.0);
+    assert_eq!(day_value(&jday, Metric::Sets), 3.0);
+}
+
+#[test]
+fn test_render_calendar() {
+    common::no_color();
+    let mut values = BTreeMap::new();
+    // 2025-01-01 is a Wednesday
+    values.insert(date("2025-01-01"), 1.0);
+    values.insert(date("2025-01-06"), 4.0);
+    let out = render_calendar(2025, &values);
+    let lines: Vec<&str> = out.lines().collect();
+    assert!(lines[0].starts_with("    Jan"));
+    assert!(lines[0].contains("Dec"));
+    assert!(lines[1].starts_with("Mon   █"));
+    assert!(lines[3].starts_with("Wed ░ ·"));
+    assert_eq!(lines.len(), 10);
+    assert!(lines[9].starts_with("2 sessions in 2025"));
+}
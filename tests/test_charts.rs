@@ -0,0 +1,37 @@
+use wxrust::charts::{downsample, line_chart, sparkline};
+
+#[test]
+fn test_sparkline() {
+    assert_eq!(sparkline(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0]), "▁▂▃▄▅▆▇█");
+    assert_eq!(sparkline(&[5.0, 5.0]), "▄▄");
+    assert_eq!(sparkline(&[]), "");
+}
+
+#[test]
+fn test_downsample_keeps_peaks() {
+    assert_eq!(downsample(&[1.0, 5.0, 2.0, 3.0], 2), vec![5.0, 3.0]);
+    assert_eq!(downsample(&[1.0, 2.0], 10), vec![1.0, 2.0]);
+}
+
+#[test]
+fn test_line_chart() {
+    let chart = line_chart(&[100.0, 110.0, 130.0], 4, |v| format!("{:.0}", v));
+    let expected = "\
+130 ┤  ●
+    ┤  │
+    ┤ ●
+100 ┤●
+    └───";
+    assert_eq!(chart, expected);
+}
+
+#[test]
+fn test_line_chart_flat_stretch() {
+    let chart = line_chart(&[100.0, 100.0, 110.0], 3, |v| format!("{:.0}", v));
+    let expected = "\
+110 ┤  ●
+    ┤  │
+100 ┤●●
+    └───";
+    assert_eq!(chart, expected);
+}
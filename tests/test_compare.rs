@@ -0,0 +1,43 @@
+mod common;
+
+use wxrust::compare::{render_compare, summarize_period};
+use common::{day_bw, set_rpe};
+
+#[test]
+fn test_summarize_period() {
+    let days = vec![
+        ("2025-09-01".to_string(), day_bw(Some(80.0), "squat", vec![set_rpe(100.0, 5, Some(7.0)), set_rpe(120.0, 3, Some(9.0))])),
+        ("2025-09-08".to_string(), day_bw(Some(81.0), "squat", vec![set_rpe(120.0, 5, None)])),
+    ];
+    let summary = summarize_period(&days);
+    assert_eq!(summary.sessions, 2);
+    assert_eq!(summary.tonnage, 500.0 + 360.0 + 600.0);
+    assert_eq!(summary.bw, Some(81.0));
+    let squat = &summary.exercises["squat"];
+    assert_eq!(squat.sessions, 2);
+    assert_eq!(squat.top.as_ref().unwrap().r, Some(5));
+    assert_eq!(squat.avg_rpe(), Some(8.0));
+}
+
+#[test]
+fn test_render_compare() {
+    common::no_color();
+    let a = summarize_period(&[
+        ("2025-09-01".to_string(), day_bw(Some(80.0), "squat", vec![set_rpe(100.0, 5, Some(8.0))])),
+        ("2025-09-03".to_string(), day_bw(None, "bench", vec![set_rpe(80.0, 5, None)])),
+    ]);
+    let b = summarize_period(&[
+        ("2025-10-01".to_string(), day_bw(Some(81.5), "squat", vec![set_rpe(110.0, 5, Some(7.5))])),
+    ]);
+    let out = render_compare("2025-09", "2025-10", &a, &b, false, |bw| bw);
+    assert!(out.starts_with("2025-09 → 2025-10\n"));
+    assert!(out.contains("sessions    2 → 1 (-50%)"));
+    assert!(out.contains("bodyweight  80.0 → 81.5 (+1.5)"));
+    let squat = out.lines().find(|l| l.starts_with("squat")).unwrap();
+    assert!(squat.contains("500 → 550 (+10%)"));
+    assert!(squat.contains("100x5 → 110x5"));
+    assert!(squat.contains("8.0 → 7.5 (-6.3%)"));
+    let bench = out.lines().find(|l| l.starts_with("bench")).unwrap();
+    assert!(bench.contains("1 → -"));
+    assert!(bench.contains("80x5 → -"));
+}
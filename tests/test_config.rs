@@ -0,0 +1,31 @@
+use std::io::Write;
+use tempfile::NamedTempFile;
+use wxrust::config;
+use wxrust::parser::Unit;
+
+#[test]
+fn test_load_config() {
+    let mut file = NamedTempFile::new().unwrap();
+    write!(file, r#"{{"target_sessions_per_week": 3.5}}"#).unwrap();
+    let cfg = config::load(file.path().to_str(), "/nonexistent").unwrap();
+    assert_eq!(cfg.target_sessions_per_week, Some(3.5));
+}
+
+#[test]
+fn test_load_missing_or_broken_config() {
+    // only the default file may be missing
+    let home = tempfile::tempdir().unwrap();
+    let cfg = config::load(None, home.path().to_str().unwrap()).unwrap();
+    assert_eq!(cfg.target_sessions_per_week, None);
+    let dir = home.path().join(".config/wxrust");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("config.json"), r#"{"unit": "lb"}"#).unwrap();
+    let cfg = config::load(None, home.path().to_str().unwrap()).unwrap();
+    assert_eq!(cfg.unit, Some(Unit::Lb));
+    let err = config::load(Some("/nonexistent/wxrust/config.json"), home.path().to_str().unwrap()).unwrap_err();
+    assert!(err.starts_with("Cannot read config file /nonexistent/wxrust/config.json"), "{}", err);
+
+    let mut file = NamedTempFile::new().unwrap();
+    write!(file, "not json").unwrap();
+    assert!(config::load(file.path().to_str(), "/nonexistent").is_err());
+}
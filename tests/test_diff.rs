@@ -0,0 +1,60 @@
+mod common;
+
+use wxrust::diff::{diff_workouts, render_diff};
+use common::{set_x, workout};
+
+#[test]
+fn test_diff_workouts_lines_up_exercises() {
+    let a = workout("", &[("1", "squat", vec![set_x(100.0, 5, 3)]), ("2", "bench", vec![set_x(80.0, 5, 1)])]);
+    let b = workout("", &[("1", "squat", vec![set_x(105.0, 5, 2)]), ("3", "deadlift", vec![set_x(140.0, 5, 1)])]);
+    let diffs = diff_workouts(&a, &b, None);
+    assert_eq!(diffs.len(), 3);
+    assert_eq!(diffs[0].exercise.name, "squat");
+    assert_eq!(diffs[0].a.len(), 3);
+    assert_eq!(diffs[0].b.len(), 2);
+    assert!(diffs[1].removed());
+    assert!(diffs[2].added());
+}
+
+#[test]
+fn test_diff_workouts_matches_by_name() {
+    let a = workout("", &[("1", "Squat", vec![set_x(100.0, 5, 1)])]);
+    let b = workout("", &[("9", "squat", vec![set_x(100.0, 6, 1)])]);
+    let diffs = diff_workouts(&a, &b, Some("#squat"));
+    assert_eq!(diffs.len(), 1);
+    assert!(!diffs[0].added() && !diffs[0].removed());
+}
+
+#[test]
+fn test_diff_workouts_pairs_by_id() {
+    // a renamed exercise keeps its id
+    let a = workout("", &[("1", "ssb squat", vec![set_x(100.0, 5, 1)])]);
+    let b = workout("", &[("1", "safety-squat #sq", vec![set_x(105.0, 5, 1)])]);
+    let diffs = diff_workouts(&a, &b, None);
+    assert_eq!(diffs.len(), 1);
+    assert!(!diffs[0].added() && !diffs[0].removed());
+
+    let a = workout("", &[("1", "bench-press #bp", vec![set_x(75.0, 5, 1)])]);
+    let b = workout("", &[("2", "deadlift #dl", vec![set_x(100.0, 5, 1)])]);
+    let diffs = diff_workouts(&a, &b, None);
+    assert_eq!(diffs.len(), 2);
+    assert!(diffs[0].removed());
+    assert!(diffs[1].added());
+}
+
+#[test]
+fn test_render_diff() {
+    common::no_color();
+    let a = workout("", &[("1", "squat", vec![set_x(100.0, 5, 2)])]);
+    let b = workout("", &[("1", "squat", vec![set_x(105.0, 5, 1), set_x(100.0, 6, 1), set_x(100.0, 5, 1)])]);
+    let text = render_diff("2025-10-24", "2025-10-31", &diff_workouts(&a, &b, None));
+    let expected = "\
+2025-10-24 → 2025-10-31
+#squat
+  100 x 5 → 105 x 5  w +5
+  100 x 5 → 100 x 6  r +1
++ 100 x 5
+
+#squat  vol 1000 → 1625 (+625)  top 100x5 → 105x5 (+5)";
+    assert_eq!(text, expected);
+}
@@ -1,5 +1,8 @@
+mod common;
+
 use wxrust::formatters::*;
 use wxrust::models::{JDay, Set, Exercise, ExerciseWrapper, EBlock};
+use common::set_rpe;
 
 #[test]
 fn test_format_weight() {
@@ -22,7 +25,7 @@ fn test_format_set() {
     // Without color: "135 x 5 @8 comment"
     // But with color, it will have ANSI codes
     // For test, disable color
-    unsafe { std::env::set_var("WXRUST_COLOR", "never"); }
+    common::no_color();
     let formatted_no_color = format_set(&set);
     assert_eq!(formatted_no_color, "135 x 5 @8 comment");
 }
@@ -33,7 +36,7 @@ fn test_compress_sets_same_weight() {
         Set { w: Some(135.0), r: Some(5), s: Some(1), lb: Some(0.0), ..Default::default() },
         Set { w: Some(135.0), r: Some(3), s: Some(1), lb: Some(0.0), ..Default::default() },
     ];
-    unsafe { std::env::set_var("WXRUST_COLOR", "never"); }
+    common::no_color();
     let compressed = compress_sets(&sets);
     assert_eq!(compressed, vec!["135 x 5, 3".to_string()]);
 }
@@ -44,7 +47,7 @@ fn test_compress_sets_same_reps() {
         Set { w: Some(135.0), r: Some(5), s: Some(1), lb: Some(0.0), ..Default::default() },
         Set { w: Some(145.0), r: Some(5), s: Some(1), lb: Some(0.0), ..Default::default() },
     ];
-    unsafe { std::env::set_var("WXRUST_COLOR", "never"); }
+    common::no_color();
     let compressed = compress_sets(&sets);
     assert_eq!(compressed, vec!["135, 145 x 5".to_string()]);
 }
@@ -55,13 +58,60 @@ fn test_compress_sets_no_compression() {
         Set { w: Some(135.0), r: Some(5), s: Some(1), lb: Some(0.0), ..Default::default() },
         Set { w: Some(145.0), r: Some(3), s: Some(1), lb: Some(0.0), ..Default::default() },
     ];
-    unsafe { std::env::set_var("WXRUST_COLOR", "never"); }
+    common::no_color();
     let compressed = compress_sets(&sets);
     assert_eq!(compressed.len(), 2);
     assert_eq!(compressed[0], "135 x 5");
     assert_eq!(compressed[1], "145 x 3");
 }
 
+#[test]
+fn test_compress_sets_keeps_comments() {
+    let sets = vec![
+        Set { w: Some(135.0), r: Some(5), s: Some(1), lb: Some(0.0), ..Default::default() },
+        Set { w: Some(135.0), r: Some(5), s: Some(1), lb: Some(0.0), c: Some("AMRAP".to_string()), ..Default::default() },
+    ];
+    common::no_color();
+    assert_eq!(compress_sets(&sets), vec!["135 x 5, 5 AMRAP"]);
+    // "x 2" would put the comment on both sets
+    let sets = vec![sets[1].clone(), sets[0].clone(), sets[0].clone()];
+    assert_eq!(compress_sets(&sets), vec!["135 x 5 AMRAP", "135 x 5 x 2"]);
+}
+
+#[test]
+fn test_compress_sets_identical() {
+    common::no_color();
+    let sets = vec![set_rpe(100.0, 5, None); 5];
+    assert_eq!(compress_sets(&sets), vec!["100 x 5 x 5"]);
+    let sets = vec![set_rpe(100.0, 5, Some(7.0)), set_rpe(100.0, 5, Some(8.0)), set_rpe(100.0, 5, Some(8.5)), set_rpe(100.0, 3, Some(9.0))];
+    assert_eq!(compress_sets(&sets), vec!["100 x 5 x 3 @7, 8, 8.5", "100 x 3 @9"]);
+    // RPE on some sets only: not merged
+    let sets = vec![set_rpe(100.0, 5, None), set_rpe(100.0, 5, Some(8.0))];
+    assert_eq!(compress_sets(&sets), vec!["100 x 5", "100 x 5 @8"]);
+}
+
+#[test]
+fn test_compress_sets_ramps() {
+    common::no_color();
+    let sets: Vec<Set> = [60.0, 80.0, 100.0, 120.0].iter().map(|&w| set_rpe(w, 5, None)).collect();
+    assert_eq!(compress_sets(&sets), vec!["60..120/20 x 5"]);
+    let sets: Vec<Set> = [5, 4, 3, 2].iter().map(|&r| set_rpe(100.0, r, Some(8.0))).collect();
+    assert_eq!(compress_sets(&sets), vec!["100 x 5..2 @8"]);
+    // too short or uneven for a ramp
+    let sets: Vec<Set> = [60.0, 80.0, 100.0].iter().map(|&w| set_rpe(w, 5, None)).collect();
+    assert_eq!(compress_sets(&sets), vec!["60, 80, 100 x 5"]);
+    let sets: Vec<Set> = [60.0, 80.0, 100.0, 110.0].iter().map(|&w| set_rpe(w, 5, None)).collect();
+    assert_eq!(compress_sets(&sets), vec!["60, 80, 100, 110 x 5"]);
+}
+
+#[test]
+fn test_compress_sets_no_compress() {
+    common::no_color();
+    let sets = vec![set_rpe(100.0, 5, None); 3];
+    let opts = FormatOptions { no_compress: true, ..Default::default() };
+    assert_eq!(compress_sets_with(&sets, &opts), vec!["100 x 5"; 3]);
+}
+
 #[test]
 fn test_compress_sets_separated_same_weight() {
     let sets = vec![
@@ -69,7 +119,7 @@ fn test_compress_sets_separated_same_weight() {
         Set { w: Some(155.0), r: Some(3), s: Some(1), lb: Some(0.0), ..Default::default() },
         Set { w: Some(135.0), r: Some(1), s: Some(1), lb: Some(0.0), ..Default::default() },
     ];
-    unsafe { std::env::set_var("WXRUST_COLOR", "never"); }
+    common::no_color();
     let compressed = compress_sets(&sets);
     assert_eq!(compressed.len(), 3);
     assert_eq!(compressed[0], "135 x 5");
@@ -101,7 +151,7 @@ fn test_summarize_workout() {
         eblocks: vec![eblock],
         exercises: vec![ex_wrapper],
     };
-    unsafe { std::env::set_var("WXRUST_COLOR", "never"); }
+    common::no_color();
     let summary = summarize_workout(&jday);
     assert_eq!(summary, "#Squat  145x3");  // Max weight 145, max reps 3
 }
@@ -128,11 +178,14 @@ fn test_format_workout() {
         eblocks: vec![eblock],
         exercises: vec![ex_wrapper],
     };
-    unsafe { std::env::set_var("WXRUST_COLOR", "never"); }
+    common::no_color();
     let formatted = format_workout(&jday);
     assert!(formatted.contains("#Squat\n135 x 5"));
     assert!(formatted.contains("Date: 2023-10-01"));
     assert!(formatted.contains("Some text"));
+    assert_eq!(format_single_eblock(&jday, &jday.eblocks[0]), "#Squat\n135 x 5");
+    let user = wxrust::models::User { usekg: Some(1) };
+    assert_eq!(render_workout("2023-10-01", &jday, &user), format!("2023-10-01\n@ 180 bw\n{}", formatted));
 }
 
 #[test]
@@ -170,7 +223,7 @@ fn test_format_workout_multiple_eblocks() {
         eblocks: vec![eblock1, eblock2],
         exercises: vec![ex_wrapper1, ex_wrapper2],
     };
-    unsafe { std::env::set_var("WXRUST_COLOR", "never"); }
+    common::no_color();
     let formatted = format_workout(&jday);
     assert!(formatted.contains("Date: 2023-10-01"));
     assert!(formatted.contains("#Squat\n135 x 5"));
@@ -185,3 +238,195 @@ fn test_format_workout_multiple_eblocks() {
 
 
 
+
+#[test]
+fn test_format_duration() {
+    assert_eq!(format_duration(45_000.0), "0:45");
+    assert_eq!(format_duration(1_530_000.0), "25:30");
+    assert_eq!(format_duration(3_930_000.0), "1:05:30");
+    assert_eq!(format_duration(12_500.0), "0:12.5");
+}
+
+#[test]
+fn test_format_set_time_and_distance() {
+    common::no_color();
+    let run = Set { d: Some(5.0), dunit: Some("km".to_string()), t: Some(1_530_000.0), ..Default::default() };
+    assert_eq!(format_set(&run), "5 km in 25:30");
+
+    let plank = Set { t: Some(120_000.0), ..Default::default() };
+    assert_eq!(format_set(&plank), "2:00");
+
+    let carry = Set { w: Some(100.0), lb: Some(0.0), d: Some(40.0), dunit: Some("m".to_string()), ..Default::default() };
+    assert_eq!(format_set(&carry), "100 x 40 m");
+}
+
+#[test]
+fn test_format_set_readings() {
+    common::no_color();
+    let set = Set { w: Some(100.0), r: Some(3), lb: Some(0.0), speed: Some(0.62), force: Some(1250.0), ..Default::default() };
+    assert_eq!(format_set(&set), "100 x 3 0.62 m/s 1250 N");
+}
+
+#[test]
+fn test_compress_sets_keeps_timed_sets_apart() {
+    let sets = vec![
+        Set { t: Some(60_000.0), ..Default::default() },
+        Set { t: Some(60_000.0), ..Default::default() },
+    ];
+    common::no_color();
+    let compressed = compress_sets(&sets);
+    assert_eq!(compressed, vec!["1:00".to_string(), "1:00".to_string()]);
+}
+
+#[test]
+fn test_summarize_workout_distance() {
+    let jday = JDay {
+        log: "".to_string(),
+        bw: None,
+        eblocks: vec![EBlock {
+            eid: "ex1".to_string(),
+            sets: vec![
+                Set { d: Some(3.0), dunit: Some("km".to_string()), t: Some(900_000.0), ..Default::default() },
+                Set { d: Some(5.0), dunit: Some("km".to_string()), t: Some(1_530_000.0), ..Default::default() },
+            ],
+        }],
+        exercises: vec![ExerciseWrapper {
+            exercise: Exercise { id: "ex1".to_string(), name: "run".to_string(), ex_type: None },
+        }],
+    };
+    common::no_color();
+    assert_eq!(summarize_workout(&jday), "#run  5 km in 25:30");
+}
+
+#[test]
+fn test_format_set_annotated() {
+    common::no_color();
+    let opts = FormatOptions { annotate: true, ..Default::default() };
+    let set = Set { w: Some(200.0), r: Some(3), lb: Some(0.0), pr: Some(1), est1rm: Some(220.0), int: Some(0.91), ..Default::default() };
+    assert_eq!(format_set_with(&set, &opts), "200 x 3★ [e1RM 220, 91%]");
+    assert_eq!(format_set_with(&set, &FormatOptions::default()), "200 x 3");
+    // the marker sits where grouped sets put it, after the reps
+    let sets = Set { s: Some(2), ..set.clone() };
+    assert_eq!(format_set_with(&sets, &opts), "200 x 3★ x 2 [e1RM 220, 91%]");
+    assert_eq!(compress_sets_with(&[set.clone(), set], &opts), vec!["200 x 3★ x 2 [e1RM 220, 91%]"]);
+}
+
+#[test]
+fn test_compress_sets_annotated_keeps_pr_marker() {
+    common::no_color();
+    let opts = FormatOptions { annotate: true, ..Default::default() };
+    let sets = vec![
+        Set { w: Some(100.0), r: Some(5), s: Some(1), lb: Some(0.0), est1rm: Some(117.0), ..Default::default() },
+        Set { w: Some(100.0), r: Some(8), s: Some(1), lb: Some(0.0), est1rm: Some(127.0), pr: Some(1), ..Default::default() },
+    ];
+    let compressed = compress_sets_with(&sets, &opts);
+    assert_eq!(compressed, vec!["100 x 5, 8★ [e1RM 127]".to_string()]);
+}
+
+#[test]
+fn test_summarize_workout_by_mode() {
+    let jday = JDay {
+        log: "".to_string(),
+        bw: None,
+        eblocks: vec![EBlock {
+            eid: "ex1".to_string(),
+            sets: vec![
+                Set { w: Some(100.0), r: Some(5), s: Some(3), lb: Some(0.0), ..Default::default() },
+                Set { w: Some(120.0), r: Some(1), lb: Some(0.0), est1rm: Some(125.0), ..Default::default() },
+            ],
+        }],
+        exercises: vec![ExerciseWrapper {
+            exercise: Exercise { id: "ex1".to_string(), name: "Squat".to_string(), ex_type: None },
+        }],
+    };
+    common::no_color();
+    assert_eq!(summarize_workout_by(&jday, SummaryMode::Top), "#Squat  120x1");
+    assert_eq!(summarize_workout_by(&jday, SummaryMode::E1rm), "#Squat  e1RM 125");
+    assert_eq!(summarize_workout_by(&jday, SummaryMode::Volume), "#Squat  vol 1620; total  vol 1620");
+    assert_eq!(summarize_workout_by(&jday, SummaryMode::Reps), "#Squat  16 reps; total  16 reps");
+    assert_eq!(summarize_workout_by(&jday, SummaryMode::Sets), "#Squat  4 sets; total  4 sets");
+    let single = common::day("Squat", vec![common::set(100.0, 5)]);
+    assert_eq!(summarize_workout_by(&single, SummaryMode::Sets), "#Squat  1 set; total  1 set");
+    assert_eq!(
+        summarize_workout_by(&jday, SummaryMode::All),
+        "#Squat  120x1 e1RM 125 vol 1620 16 reps 4 sets; total  vol 1620 16 reps 4 sets"
+    );
+}
+
+#[test]
+fn test_format_set_bodyweight() {
+    common::no_color();
+    let set = Set { w: Some(0.0), r: Some(15), ubw: Some(1), ..Default::default() };
+    assert_eq!(format_set(&set), "BW x 15");
+    let set = Set { w: Some(25.0), r: Some(5), lb: Some(0.0), ubw: Some(1), ..Default::default() };
+    assert_eq!(format_set(&set), "BW+25 x 5");
+}
+
+#[test]
+fn test_summarize_workout_bodyweight() {
+    let jday = JDay {
+        log: "".to_string(),
+        bw: None,
+        eblocks: vec![
+            EBlock {
+                eid: "ex1".to_string(),
+                sets: vec![
+                    Set { w: Some(0.0), r: Some(12), ubw: Some(1), ..Default::default() },
+                    Set { w: Some(0.0), r: Some(15), ubw: Some(1), ..Default::default() },
+                ],
+            },
+            EBlock {
+                eid: "ex2".to_string(),
+                sets: vec![
+                    Set { w: Some(25.0), r: Some(5), lb: Some(0.0), ubw: Some(1), ..Default::default() },
+                ],
+            },
+            EBlock {
+                eid: "ex3".to_string(),
+                sets: vec![Set { r: Some(30), ..Default::default() }],
+            },
+        ],
+        exercises: vec![
+            ExerciseWrapper { exercise: Exercise { id: "ex1".to_string(), name: "pullup".to_string(), ex_type: None } },
+            ExerciseWrapper { exercise: Exercise { id: "ex2".to_string(), name: "dip".to_string(), ex_type: None } },
+            ExerciseWrapper { exercise: Exercise { id: "ex3".to_string(), name: "pushup".to_string(), ex_type: None } },
+        ],
+    };
+    common::no_color();
+    assert_eq!(summarize_workout(&jday), "#pullup  BWx15; #dip  BW+25x5; #pushup  BWx30");
+}
+
+#[test]
+fn test_summarize_bodyweight_from_server() {
+    // the server sends the total load of a bodyweight exercise, unmarked
+    let set = |w: f32, r: u32| Set { w: Some(w), r: Some(r), s: Some(3), lb: Some(1.0), ..Default::default() };
+    let jday = JDay {
+        log: "EBLOCK:ex1".to_string(),
+        bw: Some(97.52),
+        eblocks: vec![EBlock { eid: "ex1".to_string(), sets: vec![set(97.52, 5), set(108.86, 3)] }],
+        exercises: vec![ExerciseWrapper {
+            exercise: Exercise { id: "ex1".to_string(), name: "chinup".to_string(), ex_type: Some("bodyweight".to_string()) },
+        }],
+    };
+    common::no_color();
+    assert_eq!(summarize_workout(&jday), "#chinup  BW+25x3");
+    // the total counts the same added load as the block
+    assert_eq!(summarize_workout_by(&jday, SummaryMode::Volume), "#chinup  vol 225; total  vol 225");
+    // showing the workout keeps the loads as sent
+    assert_eq!(format_workout(&jday), "#chinup\n215 x 5 x 3\n240 x 3 x 3");
+}
+
+#[test]
+fn test_render_table() {
+    common::no_color();
+    let rows = vec![
+        vec!["squat".to_string(), "5".to_string()],
+        vec!["bench-press".to_string(), "12".to_string()],
+    ];
+    let expected = "\
+name         sets
+-----------  ----
+squat           5
+bench-press    12";
+    assert_eq!(render_table(&["name", "sets"], &rows), expected);
+}
@@ -0,0 +1,50 @@
+mod common;
+
+use wxrust::history::{exercise_history_with, render_history};
+use common::{logged_day, set};
+
+#[test]
+fn test_tags() {
+    let jday = logged_day("#squat-day\nEBLOCK:1\nEBLOCK:2 #bp #press\n", &[("safety-squat #sq", vec![]), ("bench press", vec![])]);
+    // "#squat-day" is a note above the block, not one of its tags
+    assert_eq!(jday.tags(&jday.eblocks[0]), vec!["sq"]);
+    assert!(!jday.eblock_matches(&jday.eblocks[0], "squat-day"));
+    assert_eq!(jday.tags(&jday.eblocks[1]), vec!["bp", "press"]);
+    assert!(jday.eblock_matches(&jday.eblocks[0], "safety-squat"));
+    assert!(jday.eblock_matches(&jday.eblocks[0], "#SQ"));
+    assert!(jday.eblock_matches(&jday.eblocks[1], "bench press"));
+    assert!(jday.eblock_matches(&jday.eblocks[1], "2"));
+    assert!(!jday.eblock_matches(&jday.eblocks[1], "sq"));
+}
+
+#[test]
+fn test_exercise_history() {
+    common::no_color();
+    let jday = logged_day("#squat-day\nEBLOCK:1\nEBLOCK:2 #bp #press\n", &[
+        ("safety-squat #sq", vec![set(100.0, 5), set(100.0, 5)]),
+        ("bench press", vec![set(80.0, 8)]),
+    ]);
+    let days = vec![
+        ("2025-10-01".to_string(), jday.clone()),
+        ("2025-10-08".to_string(), jday),
+    ];
+    let entries = exercise_history_with(&days, "bp", None);
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].date, "2025-10-08");
+    assert_eq!(entries[0].sets, vec!["80 x 8"]);
+
+    let out = render_history(&exercise_history_with(&days, "sq", None));
+    let lines: Vec<&str> = out.lines().collect();
+    assert_eq!(lines[0], "date        sets");
+    assert_eq!(lines[2], "2025-10-08  100 x 5 x 2");
+}
+
+#[test]
+fn test_render_history_several_exercises() {
+    common::no_color();
+    let jday = logged_day("EBLOCK:1 #main\nEBLOCK:2 #main\n", &[("safety-squat #sq", vec![set(100.0, 5)]), ("bench press", vec![set(80.0, 8)])]);
+    let days = vec![("2025-10-01".to_string(), jday)];
+    let out = render_history(&exercise_history_with(&days, "main", None));
+    assert!(out.contains("#safety-squat #sq"));
+    assert!(out.contains("#bench press"));
+}
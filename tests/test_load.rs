@@ -0,0 +1,54 @@
+mod common;
+
+use chrono::NaiveDate;
+use wxrust::load::{daily_loads, flag, render_load, session_load, workload, LoadBasis};
+use wxrust::models::Set;
+use common::{date, day, set_x};
+
+#[test]
+fn test_session_load() {
+    let jday = day("squat", vec![set_x(40.0, 10, 1), Set { rpe: Some(8.0), ..set_x(100.0, 5, 3) }, set_x(100.0, 3, 1)]);
+    assert_eq!(session_load(&jday, LoadBasis::Tonnage), 400.0 + 1500.0 + 300.0);
+    // the warm-up is not a working set; the set without RPE counts as 10
+    assert_eq!(session_load(&jday, LoadBasis::Rpe), 24.0 + 10.0);
+}
+
+#[test]
+fn test_daily_loads_fill_rest_days() {
+    let days = vec![("2025-10-02".to_string(), day("squat", vec![set_x(100.0, 1, 1)]))];
+    let loads = daily_loads(&days, LoadBasis::Tonnage, date("2025-10-01"), date("2025-10-03"));
+    assert_eq!(loads, vec![(date("2025-10-01"), 0.0), (date("2025-10-02"), 100.0), (date("2025-10-03"), 0.0)]);
+}
+
+#[test]
+fn test_workload() {
+    // 21 days at 100 every other day, then a week at 300 every other day
+    let start = date("2025-09-01");
+    let loads: Vec<(NaiveDate, f32)> = (0..28)
+        .map(|i| {
+            let load = if i % 2 == 1 { 0.0 } else if i < 21 { 100.0 } else { 300.0 };
+            (start + chrono::Duration::days(i), load)
+        })
+        .collect();
+    let rows = workload(&loads, date("2025-09-20"));
+    assert_eq!(rows.len(), 9);
+    assert_eq!(rows[0].date, date("2025-09-20"));
+    assert_eq!(rows[0].acwr, None);
+    let last = rows.last().unwrap();
+    assert!((last.acute - 900.0 / 7.0).abs() < 0.01);
+    assert!((last.chronic - 2000.0 / 28.0).abs() < 0.01);
+    assert!((last.acwr.unwrap() - 1.8).abs() < 0.01);
+    assert_eq!(flag(last), Some("spike"));
+    assert!(last.monotony.unwrap() > 0.0);
+    assert!((last.strain.unwrap() - 900.0 * last.monotony.unwrap()).abs() < 0.1);
+}
+
+#[test]
+fn test_render_load() {
+    common::no_color();
+    let loads = vec![(date("2025-10-01"), 100.0), (date("2025-10-02"), 0.0)];
+    let out = render_load(&workload(&loads, date("2025-10-01")), LoadBasis::Tonnage, false);
+    let lines: Vec<&str> = out.lines().collect();
+    assert!(lines[0].starts_with("date        load  acute  chronic  ACWR  monotony  strain"));
+    assert!(lines[2].starts_with("2025-10-01   100    100      100"));
+}
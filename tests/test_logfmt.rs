@@ -0,0 +1,61 @@
+mod common;
+
+use wxrust::logfmt::{format_log, render_check};
+use wxrust::parser::Unit;
+
+#[test]
+fn test_examples_are_canonical() {
+    common::no_color();
+    for name in ["2025-08-29", "2025-10-27", "2025-10-28", "2025-10-29", "2025-10-30", "2025-10-31"] {
+        let text = std::fs::read_to_string(format!("examples/{}", name)).unwrap();
+        assert_eq!(format_log(&text, Unit::Lb).unwrap(), text, "{}", name);
+    }
+    let text = std::fs::read_to_string("examples/2025-01-21").unwrap();
+    assert!(format_log(&text, Unit::Lb).unwrap().ends_with("#chinup\nBW x 5 x 3\n"));
+}
+
+#[test]
+fn test_format_log() {
+    common::no_color();
+    let text = "\n2025-11-02\nHeavy day   \n@ 80.5 bw\n\n#squat    #sq\n100x5\n  100 x 5 @ 8\n102.5 KG x 5   rpe 8.5\n\
+                225lbs , 245 lb x 3\nBW + 10 x 8\n5 KM in 25:30\n// next week   \n\n\n";
+    let formatted = format_log(text, Unit::Kg).unwrap();
+    assert_eq!(formatted, "2025-11-02\n@ 80.5 bw\nHeavy day\n\n#squat #sq\n100 x 5\n100, 102.5 x 5 @8, 8.5\n\
+                           225lb, 245lb x 3\nBW+10 x 8\n5 km in 25:30\n// next week\n");
+    assert_eq!(format_log(&formatted, Unit::Kg).unwrap(), formatted);
+    // identical sets and ramps are written compactly, losslessly
+    let formatted = format_log("#squat\n60 x 5\n80 x 5\n100 x 5\n120 x 5\n100 x 5 @8\n100 x 5 @9\n100 x 5 @9 last", Unit::Kg).unwrap();
+    assert_eq!(formatted, "#squat\n60..120/20 x 5\n100 x 5 x 2 @8, 9\n100 x 5 @9 last\n");
+    assert_eq!(format_log(&formatted, Unit::Kg).unwrap(), formatted);
+    // the same weights in a pound log
+    let formatted = format_log("#squat\n100kg x 5\n225 x 5", Unit::Lb).unwrap();
+    assert_eq!(formatted, "#squat\n100kg x 5\n225 x 5\n");
+}
+
+#[test]
+fn test_format_log_keeps_comments_among_sets() {
+    common::no_color();
+    let text = "#squat\n100 x 5\n100 x 5\n// knee caved on this one\n100 x 5\n// belt on\n// from here\n120 x 3 x 2\n\n#bench\n60 x 5\n";
+    let formatted = format_log(text, Unit::Kg).unwrap();
+    assert_eq!(formatted, "#squat\n100 x 5 x 2\n// knee caved on this one\n100 x 5\n// belt on\n// from here\n120 x 3 x 2\n\n#bench\n60 x 5\n");
+    assert_eq!(format_log(&formatted, Unit::Kg).unwrap(), formatted);
+}
+
+#[test]
+fn test_format_log_error() {
+    let e = format_log("#squat\n100 x 5 @\n", Unit::Kg).unwrap_err();
+    assert_eq!(e.to_string(), "2:10: expected an RPE");
+}
+
+#[test]
+fn test_render_check() {
+    assert_eq!(render_check("a.log", "#squat\n100 x 5\n", "#squat\n100 x 5\n"), None);
+    assert_eq!(
+        render_check("a.log", "#squat\n100x5\n100 x 3\n", "#squat\n100 x 5, 3\n").unwrap(),
+        "Diff in a.log at line 2:\n-100x5\n-100 x 3\n+100 x 5, 3",
+    );
+    assert_eq!(
+        render_check("a.log", "#squat", "#squat\n").unwrap(),
+        "Diff in a.log at line 2:\n\\ newline at end of file",
+    );
+}
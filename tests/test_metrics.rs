@@ -0,0 +1,83 @@
+use wxrust::metrics::*;
+use wxrust::models::Set;
+use wxrust::rpe::RpeTable;
+
+#[test]
+fn test_epley() {
+    assert_eq!(epley(100.0, 1), 100.0);
+    assert!((epley(100.0, 5) - 116.666_67).abs() < 0.01);
+}
+
+#[test]
+fn test_set_e1rm_prefers_server_estimate() {
+    let set = Set { w: Some(100.0), r: Some(5), est1rm: Some(120.0), ..Default::default() };
+    assert_eq!(set_e1rm(&set), 120.0);
+    let set = Set { w: Some(100.0), r: Some(5), ..Default::default() };
+    assert!((set_e1rm(&set) - 116.666_67).abs() < 0.01);
+    let plank = Set { t: Some(60_000.0), ..Default::default() };
+    assert_eq!(set_e1rm(&plank), 0.0);
+}
+
+#[test]
+fn test_volume_and_reps() {
+    let sets = vec![
+        Set { w: Some(100.0), r: Some(5), s: Some(3), ..Default::default() },
+        Set { w: Some(120.0), r: Some(2), ..Default::default() },
+    ];
+    assert_eq!(total_volume(&sets), 1740.0);
+    assert_eq!(total_reps(&sets), 17);
+}
+
+#[test]
+fn test_working_sets_skip_warmups() {
+    let sets = vec![
+        Set { w: Some(20.0), r: Some(10), ..Default::default() },
+        Set { w: Some(50.0), r: Some(5), ..Default::default() },
+        Set { w: Some(100.0), r: Some(5), s: Some(3), ..Default::default() },
+    ];
+    assert_eq!(working_sets(&sets), 3);
+    let pullups = vec![Set { r: Some(10), s: Some(3), ..Default::default() }];
+    assert_eq!(working_sets(&pullups), 3);
+}
+
+#[test]
+fn test_classify() {
+    use wxrust::models::Exercise;
+    let ex = |t: Option<&str>| Exercise { id: "1".to_string(), name: "x".to_string(), ex_type: t.map(str::to_string) };
+    let weighted = vec![Set { w: Some(100.0), r: Some(5), ..Default::default() }];
+    let reps_only = vec![Set { r: Some(10), ..Default::default() }];
+    let timed = vec![Set { t: Some(60_000.0), ..Default::default() }];
+    let run = vec![Set { d: Some(5.0), t: Some(1_500_000.0), ..Default::default() }];
+    assert_eq!(classify(&ex(None), &weighted), BlockKind::Weighted);
+    assert_eq!(classify(&ex(None), &reps_only), BlockKind::Bodyweight);
+    assert_eq!(classify(&ex(None), &timed), BlockKind::Timed);
+    assert_eq!(classify(&ex(None), &run), BlockKind::Distance);
+    // the exercise type wins over what the sets look like
+    assert_eq!(classify(&ex(Some("bodyweight")), &weighted), BlockKind::Bodyweight);
+    assert_eq!(classify(&ex(Some("isometric")), &weighted), BlockKind::Timed);
+    assert_eq!(classify(&ex(Some("cardio")), &timed), BlockKind::Distance);
+    assert_eq!(classify(&ex(Some("strength")), &reps_only), BlockKind::Weighted);
+    let weighted_chins = vec![Set { w: Some(20.0), r: Some(5), ubw: Some(1), ..Default::default() }];
+    assert_eq!(classify(&ex(None), &weighted_chins), BlockKind::Bodyweight);
+}
+
+#[test]
+fn test_formulas() {
+    assert!((brzycki(100.0, 5) - 112.5).abs() < 0.01);
+    assert!((wathan(100.0, 5) - 116.6).abs() < 0.1);
+    assert!((lombardi(100.0, 5) - 117.46).abs() < 0.01);
+    assert_eq!(brzycki(100.0, 1), 100.0);
+}
+
+#[test]
+fn test_estimate_1rm() {
+    let set = Set { w: Some(100.0), r: Some(3), rpe: Some(8.0), est1rm: Some(110.0), ..Default::default() };
+    let table = RpeTable::default();
+    assert_eq!(estimate_1rm(&set, Formula::Server, &table), 110.0);
+    assert!((estimate_1rm(&set, Formula::Epley, &table) - 110.0).abs() < 0.01);
+    assert!((estimate_1rm(&set, Formula::Rpe, &table) - 115.87).abs() < 0.01);
+    let no_rpe = Set { rpe: None, ..set };
+    assert_eq!(estimate_1rm(&no_rpe, Formula::Rpe, &table), 0.0);
+    let bw = Set { r: Some(10), ubw: Some(1), ..Default::default() };
+    assert_eq!(estimate_1rm(&bw, Formula::Epley, &table), 0.0);
+}
@@ -0,0 +1,80 @@
+mod common;
+
+use std::collections::BTreeMap;
+use wxrust::formatters::SummaryMode;
+use wxrust::muscles::{render_volume, summarize_by_pattern, weekly_volume, Mapping, MuscleMap, VolumeBy};
+use common::{set_x, workout};
+
+#[test]
+fn test_lookup() {
+    let map = MuscleMap::default();
+    let jday = workout("#sq\nEBLOCK:1\nEBLOCK:2\nEBLOCK:3\nEBLOCK:4\n", &[
+        ("1", "safety-box-squat", vec![]),
+        ("2", "cambered-ohp", vec![]),
+        ("3", "Bench Press", vec![]),
+        ("4", "juggling", vec![]),
+    ]);
+    assert_eq!(map.lookup(&jday, &jday.eblocks[0]).unwrap().pattern, "squat");
+    assert_eq!(map.lookup(&jday, &jday.eblocks[1]).unwrap().pattern, "vertical push");
+    assert_eq!(map.lookup(&jday, &jday.eblocks[2]).unwrap().muscles, vec!["chest", "triceps", "front delts"]);
+    assert!(map.lookup(&jday, &jday.eblocks[3]).is_none());
+}
+
+#[test]
+fn test_lookup_press_variants() {
+    let map = MuscleMap::default();
+    let jday = workout("", &[
+        ("1", "dumbbell-bench-press", vec![]),
+        ("2", "Incline Bench Press", vec![]),
+        ("3", "close-grip-bench-press", vec![]),
+        ("4", "bench-press-paused", vec![]),
+        ("5", "push-press", vec![]),
+        ("6", "squat-paused", vec![]),
+    ]);
+    let patterns: Vec<&str> = jday.eblocks.iter().map(|b| map.lookup(&jday, b).unwrap().pattern.as_str()).collect();
+    assert_eq!(patterns, vec!["horizontal push", "horizontal push", "horizontal push", "horizontal push", "vertical push", "squat"]);
+}
+
+#[test]
+fn test_overrides() {
+    let mut overrides = BTreeMap::new();
+    overrides.insert("#Juggling".to_string(), Mapping { pattern: "carry".to_string(), muscles: vec!["forearms".to_string()] });
+    let map = MuscleMap::with_overrides(&overrides);
+    let jday = workout("", &[("1", "juggling", vec![])]);
+    assert_eq!(map.lookup(&jday, &jday.eblocks[0]).unwrap().muscles, vec!["forearms"]);
+}
+
+#[test]
+fn test_weekly_volume() {
+    common::no_color();
+    let map = MuscleMap::default();
+    let days = vec![
+        ("2025-10-06".to_string(), workout("", &[("1", "squat", vec![set_x(100.0, 5, 3)]), ("2", "bench", vec![set_x(80.0, 5, 3)])])),
+        ("2025-10-08".to_string(), workout("", &[("1", "deadlift", vec![set_x(40.0, 5, 1), set_x(150.0, 5, 2)])])),
+        ("2025-10-13".to_string(), workout("", &[("1", "juggling", vec![set_x(0.0, 10, 1)])])),
+    ];
+    let weeks = weekly_volume(&days, VolumeBy::Muscle, &map);
+    assert_eq!(weeks.len(), 2);
+    assert_eq!(weeks[0].sets["glutes"], 5);
+    assert_eq!(weeks[0].sets["chest"], 3);
+    assert_eq!(weeks[1].sets["unmapped"], 1);
+
+    let weeks = weekly_volume(&days, VolumeBy::Pattern, &map);
+    assert_eq!(weeks[0].sets["hinge"], 2);
+    let out = render_volume(&weeks, VolumeBy::Pattern);
+    let lines: Vec<&str> = out.lines().collect();
+    assert_eq!(lines[0], "week        pattern          hard sets");
+    assert_eq!(lines[2], "2025-10-06  hinge                    2");
+}
+
+#[test]
+fn test_summarize_by_pattern() {
+    common::no_color();
+    let jday = workout("", &[
+        ("1", "squat", vec![set_x(100.0, 5, 1)]),
+        ("2", "deadlift", vec![set_x(150.0, 3, 1)]),
+        ("3", "front-squat", vec![set_x(80.0, 5, 1)]),
+    ]);
+    let summary = summarize_by_pattern(&jday, SummaryMode::Top, &MuscleMap::default());
+    assert_eq!(summary, "squat: #squat  100x5, #front-squat  80x5; hinge: #deadlift  150x3");
+}
@@ -0,0 +1,144 @@
+mod common;
+
+use wxrust::formatters::{render_workout_with, FormatOptions};
+use wxrust::models::User;
+use wxrust::parser::{parse, ParseError, Unit};
+
+fn sets_of(line: &str) -> Vec<wxrust::models::Set> {
+    let workout = parse(&format!("#squat\n{}", line), Unit::Kg).unwrap();
+    workout.jday.eblocks[0].sets.clone()
+}
+
+fn error_of(text: &str) -> ParseError {
+    parse(text, Unit::Kg).unwrap_err()
+}
+
+#[test]
+fn test_examples_round_trip() {
+    common::no_color();
+    let user = User { usekg: Some(0) };
+    for name in ["2025-08-29", "2025-10-27", "2025-10-28", "2025-10-29", "2025-10-30", "2025-10-31"] {
+        let text = std::fs::read_to_string(format!("examples/{}", name)).unwrap();
+        let workout = parse(&text, Unit::Lb).unwrap();
+        assert_eq!(workout.date.as_deref(), Some(name));
+        let rendered = render_workout_with(name, &workout.jday, &user, &FormatOptions::default());
+        assert_eq!(rendered, text.trim_end(), "{}", name);
+    }
+}
+
+#[test]
+fn test_parse_structure() {
+    let text = std::fs::read_to_string("examples/2025-10-27").unwrap();
+    let workout = parse(&text, Unit::Lb).unwrap();
+    let jday = &workout.jday;
+    assert!((jday.bw.unwrap() - 222.0 / 2.20462).abs() < 0.01);
+    assert!(jday.log.starts_with("531 squat C23 W3\nTM: 465\n\nEBLOCK:safety-squat-#sq\n// https://"));
+    assert_eq!(jday.exercises[0].exercise.name, "safety-squat #sq");
+    assert_eq!(jday.tags(&jday.eblocks[0]), vec!["sq"]);
+    let sets = &jday.eblocks[0].sets;
+    // "405, 445 x 3" is two sets
+    assert_eq!(sets.len(), 7);
+    assert_eq!(sets[4].r, Some(3));
+    assert!((sets[5].w.unwrap() * 2.20462 - 445.0).abs() < 0.01);
+    assert_eq!(sets[5].lb, Some(1.0));
+    assert_eq!(sets[6].c.as_deref(), Some("AMRAP"));
+}
+
+#[test]
+fn test_parse_sets() {
+    let sets = sets_of("100 x 5 x 3 @8.5");
+    assert_eq!((sets[0].w, sets[0].r, sets[0].s, sets[0].rpe), (Some(100.0), Some(5), Some(3), Some(8.5)));
+
+    let sets = sets_of("60 x 8, 7, 6 rpe 9 last one slow");
+    assert_eq!(sets.iter().map(|s| s.r.unwrap()).collect::<Vec<_>>(), vec![8, 7, 6]);
+    assert!(sets.iter().all(|s| s.rpe == Some(9.0)));
+    assert_eq!(sets[1].c, None);
+    assert_eq!(sets[2].c.as_deref(), Some("last one slow"));
+
+    let sets = sets_of("BW+20 x 5");
+    assert_eq!((sets[0].w, sets[0].ubw), (Some(20.0), Some(1)));
+    let sets = sets_of("bw-15lb x 8");
+    assert_eq!((sets[0].ubw, sets[0].lb), (Some(1), Some(1.0)));
+    assert!((sets[0].w.unwrap() + 15.0 / 2.20462).abs() < 0.01);
+
+    let sets = sets_of("5 km in 25:30");
+    assert_eq!((sets[0].d, sets[0].dunit.as_deref(), sets[0].t), (Some(5.0), Some("km"), Some(1_530_000.0)));
+    let sets = sets_of("1:02:03.5 x 2");
+    assert_eq!((sets[0].t, sets[0].s), (Some(3_723_500.0), Some(2)));
+    let sets = sets_of("60..120/20 x 5");
+    assert_eq!(sets.iter().map(|s| s.w.unwrap()).collect::<Vec<_>>(), vec![60.0, 80.0, 100.0, 120.0]);
+    let sets = sets_of("100 x 5..3 @7, 8, 9");
+    assert_eq!(sets.iter().map(|s| (s.r.unwrap(), s.rpe.unwrap())).collect::<Vec<_>>(), vec![(5, 7.0), (4, 8.0), (3, 9.0)]);
+    // an RPE per set splits "x 3" into three sets
+    let sets = sets_of("100 x 5 x 3 @7, 8, 9 AMRAP");
+    assert_eq!(sets.iter().map(|s| (s.s.unwrap(), s.rpe.unwrap())).collect::<Vec<_>>(), vec![(1, 7.0), (1, 8.0), (1, 9.0)]);
+    assert_eq!(sets[2].c.as_deref(), Some("AMRAP"));
+
+    let sets = sets_of("100 x 40 m 0.8 m/s");
+    assert_eq!((sets[0].w, sets[0].d, sets[0].speed), (Some(100.0), Some(40.0), Some(0.8)));
+}
+
+#[test]
+fn test_blocks_and_free_text() {
+    let text = "2025-11-01\n@ 80 bw\n5x5 day\n\n#squat\n100 x 5\n// felt good\n100 x 5\nnotes\n100 x 5\n\n#bench press #bp\n60 x 10";
+    let workout = parse(text, Unit::Kg).unwrap();
+    let jday = &workout.jday;
+    assert_eq!(jday.bw, Some(80.0));
+    // free text ends a block, a "//" comment does not
+    assert_eq!(jday.log, "5x5 day\n\nEBLOCK:squat\n// felt good\nEBLOCK:squat\nnotes\n100 x 5\n\nEBLOCK:bench-press-#bp");
+    assert_eq!(jday.eblocks.iter().map(|b| b.sets.len()).collect::<Vec<_>>(), vec![1, 1, 1]);
+    assert_eq!(jday.exercise("bench-press-#bp").unwrap().name, "bench press #bp");
+}
+
+#[test]
+fn test_exercise_ids_across_days() {
+    let monday = parse("#Bench Press\n60 x 5\n\n#squat\n100 x 5", Unit::Kg).unwrap().jday;
+    let friday = parse("#squat\n110 x 5\n\n#bench  press\n65 x 5\n\n#squat\n60 x 10", Unit::Kg).unwrap().jday;
+    let ids = |jday: &wxrust::models::JDay| jday.eblocks.iter().map(|b| b.eid.clone()).collect::<Vec<_>>();
+    assert_eq!(ids(&monday), vec!["bench-press", "squat"]);
+    assert_eq!(ids(&friday), vec!["squat", "bench-press", "squat"]);
+    assert_eq!(friday.exercises.len(), 2);
+    // each placeholder shows its own block
+    common::no_color();
+    let text = wxrust::formatters::format_workout_with(&friday, &FormatOptions::default());
+    assert_eq!(text, "#squat\n110 x 5\n\n#bench press\n65 x 5\n\n#squat\n60 x 10");
+}
+
+#[test]
+fn test_parse_errors() {
+    let e = error_of("2025-11-01\n\n#squat\n100 x 5\n120 x\n");
+    assert_eq!((e.line, e.col, e.len), (5, 6, 1));
+    assert_eq!(e.message, "expected a number after 'x'");
+    assert_eq!(e.to_string(), "5:6: expected a number after 'x'");
+
+    let e = error_of("#squat\n  100 x 5 @11");
+    assert_eq!((e.line, e.col, e.len, e.message.as_str()), (2, 12, 2, "RPE must be between 1 and 10"));
+
+    let e = error_of("#squat\n100 x 5.5");
+    assert_eq!((e.col, e.len, e.message.as_str()), (7, 3, "reps must be a whole number"));
+
+    let e = error_of("#squat\n100 x 5 x 3 x 2");
+    assert_eq!((e.col, e.message.as_str()), (15, "too many 'x' parts, expected weight x reps x sets"));
+
+    let e = error_of("#squat\n100, 110 x 5, 6");
+    assert_eq!((e.col, e.len), (1, 15));
+
+    let e = error_of("#squat\n100 x 5, 4 @7, 8, 9");
+    assert_eq!((e.col, e.len, e.message.as_str()), (13, 7, "3 RPEs given for 2 sets"));
+
+    let e = error_of("#squat\n60..150/20 x 5");
+    assert_eq!((e.col, e.len, e.message.as_str()), (1, 10, "60..150 can't be walked in steps of 20"));
+
+    let e = error_of("@ heavy bw\n");
+    assert_eq!((e.line, e.col, e.len), (1, 3, 5));
+
+    let e = error_of("#\n");
+    assert_eq!(e.message, "missing exercise name after '#'");
+}
+
+#[test]
+fn test_render_error() {
+    let text = "#squat\n120 x\n";
+    let e = error_of(text);
+    assert_eq!(e.render("day.txt", text), "error: expected a number after 'x'\n --> day.txt:2:6\n  |\n2 | 120 x\n  |      ^");
+}
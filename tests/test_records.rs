@@ -0,0 +1,42 @@
+mod common;
+
+use wxrust::models::Set;
+use wxrust::records::{build_records, render_records};
+use common::{day, set};
+
+#[test]
+fn test_build_records() {
+    let days = vec![
+        ("2025-10-01".to_string(), day("squat", vec![set(100.0, 5), set(120.0, 1)])),
+        ("2025-10-08".to_string(), day("squat", vec![set(105.0, 5), Set { pr: Some(1), ..set(100.0, 8) }])),
+        ("2025-10-15".to_string(), day("bench", vec![set(80.0, 3)])),
+    ];
+    let all = build_records(&days, None, None);
+    assert_eq!(all.len(), 2);
+    let squat = &all["squat"];
+    let one = squat.rep_maxes[0].as_ref().unwrap();
+    assert_eq!((one.weight, one.date.as_str()), (120.0, "2025-10-01"));
+    let five = squat.rep_maxes[3].as_ref().unwrap();
+    assert_eq!((five.weight, five.date.as_str()), (105.0, "2025-10-08"));
+    let eight = squat.rep_maxes[4].as_ref().unwrap();
+    assert!(eight.server_pr);
+    assert!(squat.rep_maxes[5].is_none());
+
+    let only_bench = build_records(&days, Some("bench"), None);
+    assert_eq!(only_bench.keys().collect::<Vec<_>>(), vec!["bench"]);
+}
+
+#[test]
+fn test_render_records() {
+    common::no_color();
+    let days = vec![("2025-10-01".to_string(), day("bench", vec![Set { pr: Some(1), ..set(80.0, 3) }]))];
+    let text = render_records(&build_records(&days, None, None), false);
+    let expected = "\
+exercise  max   weight  reps  date        server PR
+--------  ----  ------  ----  ----------  ---------
+bench     1RM       80     3  2025-10-01  ✓
+          2RM       80     3  2025-10-01  ✓
+          3RM       80     3  2025-10-01  ✓
+          e1RM      88     3  2025-10-01  ✓";
+    assert_eq!(text, expected);
+}
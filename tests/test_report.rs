@@ -0,0 +1,77 @@
+mod common;
+
+use wxrust::models::Set;
+use wxrust::report::{build_report, period_bounds, previous_bounds, render_markdown, render_text, title, Period};
+use common::{date, day_bw, set, set_comment};
+
+#[test]
+fn test_period_bounds() {
+    let d = date("2025-10-08");
+    assert_eq!(period_bounds(Period::Week, d).unwrap(), (date("2025-10-06"), date("2025-10-12")));
+    assert_eq!(period_bounds(Period::Month, d).unwrap(), (date("2025-10-01"), date("2025-10-31")));
+    assert_eq!(period_bounds(Period::Year, d).unwrap(), (date("2025-01-01"), date("2025-12-31")));
+    assert_eq!(previous_bounds(Period::Month, date("2025-03-01")).unwrap(), (date("2025-02-01"), date("2025-02-28")));
+    assert_eq!(title(Period::Month, date("2025-10-01")), "October 2025");
+    assert_eq!(title(Period::Year, date("2025-01-01")), "2025 in review");
+}
+
+fn sample() -> wxrust::report::Report {
+    let days = vec![
+        ("2025-10-06".to_string(), day_bw(Some(80.0), "squat", vec![set(100.0, 5), Set { pr: Some(1), ..set(120.0, 3) }])),
+        ("2025-10-09".to_string(), day_bw(Some(79.5), "squat", vec![set_comment(110.0, 5, "knee ok")])),
+    ];
+    let previous = vec![("2025-10-01".to_string(), day_bw(None, "squat", vec![set(100.0, 5)]))];
+    build_report(Period::Week, date("2025-10-06"), date("2025-10-12"), &days, &previous, &previous)
+}
+
+#[test]
+fn test_build_report() {
+    let report = sample();
+    assert_eq!(report.total.sessions, 2);
+    assert_eq!(report.previous.sessions, 1);
+    assert_eq!(report.total.tonnage, 500.0 + 360.0 + 550.0);
+    assert_eq!(report.bw, Some((80.0, 79.5)));
+    assert_eq!(report.exercises.len(), 1);
+    assert_eq!(report.exercises[0].totals.sessions, 2);
+    assert_eq!(report.exercises[0].top.as_ref().unwrap().w, Some(120.0));
+    // 110 x 5 beats the 5RM of 100 from the week before
+    let prs: Vec<&str> = report.prs.iter().map(|h| h.date.as_str()).collect();
+    assert_eq!(prs, vec!["2025-10-06", "2025-10-09"]);
+    assert_eq!(report.comments[0].date, "2025-10-09");
+}
+
+#[test]
+fn test_report_prs_from_history() {
+    // no server flags, as with logs read from a directory
+    let earlier = vec![("2025-09-29".to_string(), day_bw(None, "squat", vec![set(100.0, 5)]))];
+    let days = vec![
+        ("2025-10-06".to_string(), day_bw(None, "squat", vec![set(100.0, 5), set(105.0, 3)])),
+        ("2025-10-07".to_string(), day_bw(None, "bench", vec![set(80.0, 5), Set { pr: Some(1), ..set(80.0, 5) }])),
+        ("2025-10-08".to_string(), day_bw(None, "bench", vec![set(80.0, 5), set(82.5, 5)])),
+    ];
+    let report = build_report(Period::Week, date("2025-10-06"), date("2025-10-12"), &days, &[], &earlier);
+    let prs: Vec<(&str, f32)> = report.prs.iter().map(|h| (h.exercise.as_str(), h.set.w.unwrap())).collect();
+    // bench has no records before the week, so the server's flag counts
+    assert_eq!(prs, vec![("squat", 105.0), ("bench", 80.0), ("bench", 82.5)]);
+}
+
+#[test]
+fn test_render_report() {
+    common::no_color();
+    let report = sample();
+    let text = render_text(&report, false, |bw| bw);
+    assert!(text.starts_with("Week of 2025-10-06 (2025-10-06 → 2025-10-12)"));
+    assert!(text.contains("sessions    2 (+1 vs previous)"));
+    assert!(text.contains("tonnage     1410 (+910)"));
+    assert!(text.contains("bodyweight  80.0 → 79.5 (-0.5)"));
+    assert!(text.contains("PRs\n  2025-10-06  #squat  120 x 3"));
+    assert!(text.contains("Comments\n  2025-10-09  #squat  110 x 5 knee ok"));
+    // the table names exercises as the highlights do
+    assert!(text.lines().any(|l| l.starts_with("#squat  ") && l.contains("120 x 3")));
+
+    let md = render_markdown(&report, false, |bw| bw);
+    assert!(md.starts_with("# Week of 2025-10-06"));
+    assert!(md.contains("- **sessions**: 2 (+1 vs previous)"));
+    assert!(md.contains("| exercise | top set | sessions | hard sets | tonnage |\n|---|---|---|---|---|\n| #squat | 120 x 3 | 2 |"));
+    assert!(md.contains("## PRs\n\n- 2025-10-06 #squat: 120 x 3"));
+}
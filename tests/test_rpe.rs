@@ -0,0 +1,73 @@
+mod common;
+
+use std::collections::BTreeMap;
+use wxrust::rpe::{render_analysis, rpe_drift, weekly_rpe, RpeTable};
+use common::{day, set_rpe};
+
+#[test]
+fn test_default_table() {
+    let table = RpeTable::default();
+    assert_eq!(table.percent(1, 10.0), Some(100.0));
+    assert_eq!(table.percent(5, 8.0), Some(81.1));
+    assert_eq!(table.percent(3, 8.0), Some(86.3));
+    assert_eq!(table.percent(12, 6.0), Some(57.4));
+    assert_eq!(table.percent(13, 8.0), None);
+    assert_eq!(table.percent(5, 5.5), None);
+    let e1rm = table.e1rm(&set_rpe(100.0, 1, Some(10.0))).unwrap();
+    assert_eq!(e1rm, 100.0);
+    assert_eq!(table.e1rm(&set_rpe(100.0, 5, None)), None);
+}
+
+#[test]
+fn test_table_overrides() {
+    let mut overrides = BTreeMap::new();
+    overrides.insert("8".to_string(), vec![90.0, 85.0]);
+    overrides.insert("5".to_string(), vec![70.0]);
+    let table = RpeTable::with_overrides(&overrides).unwrap();
+    assert_eq!(table.percent(2, 8.0), Some(85.0));
+    assert_eq!(table.percent(3, 8.0), None);
+    assert_eq!(table.percent(1, 5.0), Some(70.0));
+    assert_eq!(table.percent(1, 9.0), RpeTable::default().percent(1, 9.0));
+
+    overrides.insert("8.3".to_string(), vec![90.0]);
+    assert!(RpeTable::with_overrides(&overrides).is_err());
+    let mut bad = BTreeMap::new();
+    bad.insert("9".to_string(), vec![120.0]);
+    assert!(RpeTable::with_overrides(&bad).is_err());
+}
+
+#[test]
+fn test_render_table() {
+    common::no_color();
+    let out = RpeTable::default().render();
+    let lines: Vec<&str> = out.lines().collect();
+    assert!(lines[0].starts_with("RPE  RIR"));
+    assert!(lines[2].starts_with(" 10    0  100.0  95.5"));
+    assert_eq!(lines.len(), 11);
+}
+
+#[test]
+fn test_weekly_rpe_and_drift() {
+    common::no_color();
+    let days = vec![
+        ("2025-09-01".to_string(), day("squat", vec![set_rpe(100.0, 5, Some(7.0)), set_rpe(100.0, 5, Some(8.0)), set_rpe(60.0, 5, None)])),
+        ("2025-09-03".to_string(), day("squat", vec![set_rpe(100.0, 5, Some(8.0))])),
+        ("2025-09-08".to_string(), day("squat", vec![set_rpe(100.0, 5, Some(9.0)), set_rpe(110.0, 3, Some(9.0))])),
+    ];
+    let weeks = weekly_rpe(&days, None, &RpeTable::default());
+    assert_eq!(weeks.len(), 2);
+    assert_eq!(weeks[0].week, "2025-09-01");
+    assert_eq!(weeks[0].sets, 3);
+    assert!((weeks[0].avg_rpe - 23.0 / 3.0).abs() < 0.001);
+    assert!((weeks[0].best_e1rm - 100.0 * 100.0 / 78.6).abs() < 0.01);
+
+    // the first session's hardest 100 x 5 was @8, so @9 on 09-08 is one RPE up
+    let drifts = rpe_drift(&days, None);
+    assert_eq!(drifts.len(), 1);
+    assert_eq!(drifts[0].before, ("2025-09-03".to_string(), 8.0));
+    assert_eq!(drifts[0].after, ("2025-09-08".to_string(), 9.0));
+
+    let out = render_analysis(&weeks, &drifts, false);
+    assert!(out.contains("RPE drift at the same load:"));
+    assert!(out.contains("  #squat  100 x 5  @8 → @9  (2025-09-03 → 2025-09-08)"));
+}
@@ -0,0 +1,31 @@
+mod common;
+
+use regex::Regex;
+use wxrust::search::{render_hits, search};
+use common::{logged_day, set, set_comment};
+
+#[test]
+fn test_search_log_and_comments() {
+    common::no_color();
+    let days = vec![
+        ("2025-10-01".to_string(), logged_day("warmup\nknee pain today\nEBLOCK:1\n", &[("squat", vec![set(100.0, 5)])])),
+        ("2025-10-03".to_string(), logged_day("EBLOCK:1\n", &[("squat", vec![set_comment(100.0, 5, "belt, knee felt ok")])])),
+        ("2025-10-05".to_string(), logged_day("EBLOCK:1\n", &[("squat", vec![set(100.0, 5)])])),
+    ];
+    let hits = search(&days, &Regex::new("knee").unwrap(), 0);
+    assert_eq!(hits.len(), 2);
+    assert_eq!(hits[0].lines, vec!["  knee pain today"]);
+    assert_eq!(hits[1].lines, vec!["  #squat  100 x 5 belt, knee felt ok"]);
+
+    let out = render_hits(&hits);
+    assert!(out.starts_with("2025-10-01\n  knee pain today\n\n2025-10-03"));
+}
+
+#[test]
+fn test_search_context() {
+    let days = vec![
+        ("2025-10-01".to_string(), logged_day("a\nknee\nEBLOCK:1\nb\nc\nknee again\n", &[("squat", vec![set(100.0, 5)])])),
+    ];
+    let hits = search(&days, &Regex::new("knee").unwrap(), 1);
+    assert_eq!(hits[0].lines, vec!["  a", "  knee", "  #squat", "  --", "  c", "  knee again"]);
+}
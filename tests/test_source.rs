@@ -0,0 +1,65 @@
+use chrono::NaiveDate;
+use wxrust::parser::Unit;
+use wxrust::source::{DirSource, WorkoutSource};
+
+fn day(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+#[tokio::test]
+async fn test_dir_source_dates() {
+    let source = DirSource::open("examples", Unit::Lb).unwrap();
+    let all = source.dates(None, None, 0).await.unwrap();
+    assert_eq!(all.len(), 7);
+    assert_eq!(all[0], "2025-01-21");
+    // the most recent, oldest first, as from the API
+    assert_eq!(source.dates(None, None, 2).await.unwrap(), vec!["2025-10-30", "2025-10-31"]);
+    assert_eq!(source.dates(Some("2025-10-28".to_string()), None, 2).await.unwrap(), vec!["2025-10-27", "2025-10-28"]);
+    let october = source.dates_in_range(day(2025, 10, 1), day(2025, 10, 29)).await.unwrap();
+    assert_eq!(october, vec!["2025-10-27", "2025-10-28", "2025-10-29"]);
+}
+
+#[tokio::test]
+async fn test_dir_source_workouts() {
+    let source = DirSource::open("examples", Unit::Lb).unwrap();
+    let jday = source.workout("2025-10-29").await.unwrap();
+    assert_eq!(jday.exercises[0].exercise.name, "deadlift #dl");
+    assert_eq!(jday.eblocks[0].sets.len(), 7);
+    assert!(source.workout("2025-10-01").await.is_err());
+    assert_eq!(source.user().await.unwrap().usekg, Some(0));
+
+    let dates = vec!["2025-10-31".to_string(), "2025-10-27".to_string()];
+    let days = source.workouts(&dates).await;
+    assert_eq!(days[0].0, "2025-10-31");
+    assert!(days.iter().all(|(_, r)| r.is_ok()));
+
+    let weights = source.bodyweights(day(2025, 10, 28), day(2025, 10, 31)).await.unwrap();
+    assert_eq!(weights.len(), 4);
+    assert!((weights[3].1 * 2.20462 - 222.0).abs() < 0.01);
+}
+
+#[tokio::test]
+async fn test_dir_source_layout() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir(dir.path().join("2025")).unwrap();
+    std::fs::write(dir.path().join("2025/2025-11-03.txt"), "#squat\n100 x 5\n").unwrap();
+    // dated by its first line
+    std::fs::write(dir.path().join("2025/monday.log"), "\n2025-11-10\n#squat\n100 x 5 @\n").unwrap();
+    std::fs::write(dir.path().join("notes.md"), "ideas\n").unwrap();
+    let source = DirSource::open(dir.path().to_str().unwrap(), Unit::Kg).unwrap();
+    assert_eq!(source.dates(None, None, 0).await.unwrap(), vec!["2025-11-03", "2025-11-10"]);
+    assert_eq!(source.workout("2025-11-03").await.unwrap().eblocks[0].sets[0].w, Some(100.0));
+    let err = source.workout("2025-11-10").await.unwrap_err();
+    assert!(err.ends_with("monday.log:4:10: expected an RPE"), "{}", err);
+
+    // a second log for a date is skipped; the first path in sorted order stays
+    std::fs::write(dir.path().join("2025-11-03"), "#bench\n60 x 5\n").unwrap();
+    let source = DirSource::open(dir.path().to_str().unwrap(), Unit::Kg).unwrap();
+    assert_eq!(source.dates(None, None, 0).await.unwrap(), vec!["2025-11-03", "2025-11-10"]);
+    assert_eq!(source.workout("2025-11-03").await.unwrap().exercises[0].exercise.name, "squat");
+
+    // files that are not text are ignored
+    std::fs::write(dir.path().join("photo.jpg"), [0xff, 0xd8, 0xff, 0x00]).unwrap();
+    assert!(DirSource::open(dir.path().to_str().unwrap(), Unit::Kg).is_ok());
+}
+
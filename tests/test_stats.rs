@@ -0,0 +1,60 @@
+mod common;
+
+use chrono::NaiveDate;
+use wxrust::models::{EBlock, Exercise, ExerciseWrapper, JDay, Set};
+use wxrust::stats::{aggregate, period_label, render_stats, GroupBy};
+
+fn squat_day(w: f32) -> JDay {
+    JDay {
+        log: "".to_string(),
+        bw: None,
+        eblocks: vec![EBlock {
+            eid: "1".to_string(),
+            sets: vec![
+                Set { w: Some(40.0), r: Some(5), lb: Some(0.0), ..Default::default() },
+                Set { w: Some(w), r: Some(5), s: Some(3), lb: Some(0.0), ..Default::default() },
+            ],
+        }],
+        exercises: vec![ExerciseWrapper {
+            exercise: Exercise { id: "1".to_string(), name: "squat".to_string(), ex_type: None },
+        }],
+    }
+}
+
+#[test]
+fn test_period_label() {
+    let d = NaiveDate::from_ymd_opt(2025, 10, 31).unwrap();
+    assert_eq!(period_label(d, GroupBy::Week), "2025-10-27");
+    assert_eq!(period_label(d, GroupBy::Month), "2025-10");
+}
+
+#[test]
+fn test_aggregate_by_week() {
+    let days = vec![
+        ("2025-10-27".to_string(), squat_day(100.0)),
+        ("2025-10-29".to_string(), squat_day(110.0)),
+        ("2025-11-03".to_string(), squat_day(120.0)),
+    ];
+    let periods = aggregate(&days, GroupBy::Week);
+    assert_eq!(periods.len(), 2);
+    let squat = &periods[0].exercises["squat"];
+    assert_eq!(squat.sessions, 2);
+    assert_eq!(squat.hard_sets, 6);
+    assert_eq!(squat.reps, 40);
+    assert_eq!(squat.tonnage, 40.0 * 5.0 * 2.0 + 100.0 * 15.0 + 110.0 * 15.0);
+    assert_eq!(periods[0].total.sessions, 2);
+    assert_eq!(periods[1].period, "2025-11-03");
+}
+
+#[test]
+fn test_render_stats_by_exercise() {
+    common::no_color();
+    let days = vec![("2025-10-27".to_string(), squat_day(100.0))];
+    let text = render_stats(&aggregate(&days, GroupBy::Exercise), GroupBy::Exercise, false);
+    let expected = "\
+exercise  sessions  hard sets  reps  tonnage  top e1RM
+--------  --------  ---------  ----  -------  --------
+squat            1          3    20     1700       117
+total            1          3    20     1700";
+    assert_eq!(text, expected);
+}
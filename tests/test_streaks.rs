@@ -0,0 +1,59 @@
+mod common;
+
+use chrono::NaiveDate;
+use wxrust::streaks::{build_report, render_report, Streak};
+use common::date;
+
+fn dates(list: &[&str]) -> Vec<NaiveDate> {
+    list.iter().map(|s| date(s)).collect()
+}
+
+#[test]
+fn test_day_and_week_streaks() {
+    // weeks of 2025-09-01, 09-08, 09-15 (skipped), 09-22, 09-29
+    let days = dates(&["2025-09-01", "2025-09-02", "2025-09-03", "2025-09-10", "2025-09-23", "2025-09-30", "2025-10-01"]);
+    let report = build_report(&days, date("2025-10-02"), None);
+    assert_eq!(report.sessions, 7);
+    assert_eq!(report.longest_days, Some(Streak { start: date("2025-09-01"), end: date("2025-09-03"), len: 3 }));
+    assert_eq!(report.current_days, Some(Streak { start: date("2025-09-30"), end: date("2025-10-01"), len: 2 }));
+    assert_eq!(report.longest_weeks.unwrap().len, 2);
+    assert_eq!(report.longest_weeks.unwrap().start, date("2025-09-01"));
+    assert_eq!(report.current_weeks.unwrap().start, date("2025-09-22"));
+    // five weeks spanned
+    assert!((report.sessions_per_week - 1.4).abs() < 0.001);
+    assert_eq!(report.gaps[0].days, 12);
+    assert_eq!(report.gaps[0].from, date("2025-09-10"));
+    assert_eq!(report.gaps.len(), 3);
+}
+
+#[test]
+fn test_broken_streaks() {
+    let days = dates(&["2025-09-01", "2025-09-02"]);
+    let report = build_report(&days, date("2025-09-20"), None);
+    assert_eq!(report.current_days, None);
+    assert_eq!(report.current_weeks, None);
+    assert_eq!(report.longest_days.unwrap().len, 2);
+}
+
+#[test]
+fn test_adherence() {
+    let days = dates(&["2025-09-01", "2025-09-03", "2025-09-05", "2025-09-08", "2025-09-15", "2025-09-17", "2025-09-29"]);
+    let report = build_report(&days, date("2025-09-30"), Some(2.0));
+    let a = report.adherence.unwrap();
+    // four complete weeks before the week of 09-29
+    assert_eq!(a.weeks, 4);
+    assert_eq!(a.weeks_on_target, 2);
+    assert_eq!(a.recent_sessions, 6);
+}
+
+#[test]
+fn test_render_report() {
+    common::no_color();
+    let days = dates(&["2025-09-01", "2025-09-02", "2025-09-10"]);
+    let out = render_report(&build_report(&days, date("2025-09-11"), Some(3.0)));
+    assert!(out.contains("sessions         3"));
+    assert!(out.contains("current streak   2 weeks (2025-09-01 → 2025-09-08), 1 day (2025-09-10)"));
+    assert!(out.contains("longest gaps     7 days (2025-09-02 → 2025-09-10)"));
+    assert!(out.contains("weeks on target  0/1 (0%)"));
+    assert!(out.contains("last 4 weeks     2/12 sessions (17%)"));
+}
@@ -0,0 +1,67 @@
+mod common;
+
+use std::collections::BTreeMap;
+use wxrust::aliases::Aliases;
+use wxrust::metrics::Formula;
+use wxrust::models::{JDay, Set};
+use wxrust::rpe::RpeTable;
+use wxrust::trend::{render_trend, session_bests};
+use common::{day, set};
+
+#[test]
+fn test_session_bests() {
+    let days = vec![
+        ("2025-10-01".to_string(), day("squat", vec![set(100.0, 5), set(110.0, 1)])),
+        ("2025-10-03".to_string(), day("bench", vec![set(80.0, 5)])),
+        ("2025-10-08".to_string(), day("squat", vec![Set { est1rm: Some(130.0), ..set(110.0, 5) }])),
+    ];
+    let bests = session_bests(&days, "squat", Formula::Epley, &RpeTable::default(), None);
+    assert_eq!(bests.len(), 2);
+    assert!((bests[0].e1rm - 116.67).abs() < 0.01);
+    assert_eq!(bests[0].set.w, Some(100.0));
+    let bests = session_bests(&days, "squat", Formula::Server, &RpeTable::default(), None);
+    assert_eq!(bests[1].e1rm, 130.0);
+}
+
+#[test]
+fn test_session_bests_rpe_uses_table_and_skips_sets_without_rpe() {
+    let days = vec![
+        ("2025-10-01".to_string(), day("squat", vec![set(100.0, 1)])),
+        ("2025-10-08".to_string(), day("squat", vec![Set { rpe: Some(9.0), ..set(100.0, 1) }])),
+    ];
+    let bests = session_bests(&days, "squat", Formula::Rpe, &RpeTable::default(), None);
+    assert_eq!(bests.len(), 1);
+    assert!((bests[0].e1rm - 100.0 * 100.0 / 95.5).abs() < 0.01);
+    let table = RpeTable::with_overrides(&BTreeMap::from([("9".to_string(), vec![90.0])])).unwrap();
+    let bests = session_bests(&days, "squat", Formula::Rpe, &table, None);
+    assert!((bests[0].e1rm - 100.0 * 100.0 / 90.0).abs() < 0.01);
+}
+
+#[test]
+fn test_session_bests_by_tag_and_parent() {
+    let tagged = JDay {
+        log: "EBLOCK:1 #sq".to_string(),
+        ..day("safety-squat", vec![set(100.0, 1)])
+    };
+    let days = vec![
+        ("2025-10-01".to_string(), tagged),
+        ("2025-10-08".to_string(), day("pause-squat", vec![set(90.0, 1)])),
+    ];
+    assert_eq!(session_bests(&days, "sq", Formula::Epley, &RpeTable::default(), None).len(), 1);
+    let aliases = Aliases::new(&BTreeMap::from([("pause-squat".to_string(), "sq".to_string())]));
+    let bests = session_bests(&days, "#sq", Formula::Epley, &RpeTable::default(), Some(&aliases));
+    assert_eq!(bests.iter().map(|b| b.date.as_str()).collect::<Vec<_>>(), vec!["2025-10-01", "2025-10-08"]);
+}
+
+#[test]
+fn test_render_trend() {
+    common::no_color();
+    let days = vec![
+        ("2025-10-01".to_string(), day("squat", vec![set(100.0, 1)])),
+        ("2025-10-08".to_string(), day("squat", vec![set(110.0, 1)])),
+    ];
+    let text = render_trend(&session_bests(&days, "squat", Formula::Epley, &RpeTable::default(), None), false);
+    assert!(text.starts_with("date        best set  e1RM\n"));
+    assert!(text.contains("2025-10-08  110 x 1    110"));
+    assert!(text.ends_with("▁█  100 → 110 (+10, +10%)"));
+}
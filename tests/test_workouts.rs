@@ -1,6 +1,8 @@
+mod common;
+
 use mockall::mock;
-use wxrust::workouts::{get_jday, get_day, get_dates};
-use wxrust::models::{GraphQLResponse, WorkoutData, JDay, EBlock, ExerciseWrapper, Exercise, Set, User};
+use wxrust::workouts::{get_bodyweights, get_jday, get_jdays, get_day, get_dates};
+use wxrust::models::{GraphQLResponse, WorkoutData, JDay, EBlock, ExerciseWrapper, Exercise, Set, User, GetJRangeBwData, JRangeBwData, JRangeBwDayData};
 use base64::{Engine, engine::general_purpose};
 
 mock! {
@@ -13,6 +15,10 @@ mock! {
         async fn graphql_request<T: serde::de::DeserializeOwned + 'static>(&self, token: &str, query: &str, variables: Option<serde_json::Value>) -> Result<wxrust::models::GraphQLResponse<T>, Box<dyn std::error::Error>>;
         async fn get_user_info(&self, token: &str) -> Result<User, Box<dyn std::error::Error>>;
     }
+
+    impl Clone for ApiClient {
+        fn clone(&self) -> Self;
+    }
 }
 
 #[tokio::test]
@@ -115,6 +121,35 @@ async fn test_get_dates_success() {
     assert_eq!(dates, vec!["2023-10-01", "2023-10-02"]);
 }
 
+#[tokio::test]
+async fn test_get_dates_skips_malformed_days() {
+    let header = general_purpose::URL_SAFE_NO_PAD.encode(r#"{"alg":"HS256","typ":"JWT"}"#.as_bytes());
+    let payload = general_purpose::URL_SAFE_NO_PAD.encode(r#"{"id":123,"exp":2000000000}"#.as_bytes());
+    let token = format!("{}.{}.{}", header, payload, "signature");
+
+    let mut mock_client = MockApiClient::new();
+    mock_client
+        .expect_graphql_request::<wxrust::models::GetJRangeData>()
+        .times(1)
+        .returning(|_, _, _| {
+            Ok(GraphQLResponse {
+                data: Some(wxrust::models::GetJRangeData {
+                    jrange: Some(wxrust::models::JRangeData {
+                        days: Some(vec![
+                            wxrust::models::JRangeDayData { on: Some("2023-10".to_string()) },
+                            wxrust::models::JRangeDayData { on: Some("2023-10-01T00:00:00".to_string()) },
+                            wxrust::models::JRangeDayData { on: Some("2023-10-02".to_string()) },
+                        ]),
+                    }),
+                }),
+                errors: None,
+            })
+        });
+
+    let dates = get_dates(&mock_client, &token, None, None, 2, false).await.unwrap();
+    assert_eq!(dates, vec!["2023-10-01", "2023-10-02"]);
+}
+
 #[tokio::test]
 async fn test_get_dates_invalid_token() {
     let mock_client = MockApiClient::new();
@@ -201,11 +236,100 @@ async fn test_get_day_success() {
             })
         });
 
-    unsafe { std::env::set_var("WXRUST_COLOR", "never"); } // Disable colors for test
+    common::no_color();
     let result = get_day(&mock_client, &token, "2023-10-01").await;
     assert!(result.is_ok());
     let workout = result.unwrap();
     assert!(workout.contains("Date: 2023-10-01"));
     assert!(workout.contains("#Squat"));
     assert!(workout.contains("135 x 5"));
-}
\ No newline at end of file
+}
+#[tokio::test]
+async fn test_get_jdays_in_order() {
+    let header = general_purpose::URL_SAFE_NO_PAD.encode(r#"{"alg":"HS256","typ":"JWT"}"#.as_bytes());
+    let payload = general_purpose::URL_SAFE_NO_PAD.encode(r#"{"id":123,"exp":2000000000}"#.as_bytes());
+    let token = format!("{}.{}.{}", header, payload, "signature");
+
+    // each fetch runs on its own clone; the day's log is its date
+    let mut mock_client = MockApiClient::new();
+    mock_client.expect_clone().returning(|| {
+        let mut day_client = MockApiClient::new();
+        day_client
+            .expect_graphql_request::<wxrust::models::WorkoutData>()
+            .returning(|_, query, _| {
+                let date = query.split("ymd: \"").nth(1).unwrap()[..10].to_string();
+                if date == "2025-10-05" {
+                    panic!("lost connection");
+                }
+                Ok(GraphQLResponse {
+                    data: Some(WorkoutData {
+                        jday: Some(JDay { log: date, bw: None, eblocks: vec![], exercises: vec![] }),
+                    }),
+                    errors: None,
+                })
+            });
+        day_client
+    });
+
+    let dates: Vec<String> = (1..=20).map(|d| format!("2025-10-{:02}", d)).collect();
+    let results = get_jdays(&mock_client, &token, &dates).await;
+    assert_eq!(results.iter().map(|(d, _)| d.clone()).collect::<Vec<_>>(), dates);
+    for (date, result) in &results {
+        if date == "2025-10-05" {
+            // a failed fetch only fails its own day
+            assert!(result.is_err());
+        } else {
+            assert_eq!(&result.as_ref().unwrap().log, date);
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_get_bodyweights_skips_malformed_days() {
+    let header = general_purpose::URL_SAFE_NO_PAD.encode(r#"{"alg":"HS256","typ":"JWT"}"#.as_bytes());
+    let payload = general_purpose::URL_SAFE_NO_PAD.encode(r#"{"id":123,"exp":2000000000}"#.as_bytes());
+    let token = format!("{}.{}.{}", header, payload, "signature");
+
+    let mut mock_client = MockApiClient::new();
+    mock_client
+        .expect_graphql_request::<GetJRangeBwData>()
+        .times(1)
+        .returning(|_, _, _| {
+            let day = |on: &str, bw: f32| JRangeBwDayData { on: Some(on.to_string()), bw: Some(bw) };
+            Ok(GraphQLResponse {
+                data: Some(GetJRangeBwData {
+                    jrange: Some(JRangeBwData { days: Some(vec![day("2025-10-01T00:00:00", 80.5), day("2025-10", 81.0)]) }),
+                }),
+                errors: None,
+            })
+        });
+
+    let oldest = chrono::NaiveDate::from_ymd_opt(2025, 10, 1).unwrap();
+    let latest = chrono::NaiveDate::from_ymd_opt(2025, 10, 31).unwrap();
+    let weights = get_bodyweights(&mock_client, &token, oldest, latest).await.unwrap();
+    assert_eq!(weights, vec![("2025-10-01".to_string(), 80.5)]);
+}
+
+#[tokio::test]
+async fn test_get_bodyweights_error() {
+    let header = general_purpose::URL_SAFE_NO_PAD.encode(r#"{"alg":"HS256","typ":"JWT"}"#.as_bytes());
+    let payload = general_purpose::URL_SAFE_NO_PAD.encode(r#"{"id":123,"exp":2000000000}"#.as_bytes());
+    let token = format!("{}.{}.{}", header, payload, "signature");
+
+    // only an unknown bw field falls back to fetching every day
+    let mut mock_client = MockApiClient::new();
+    mock_client
+        .expect_graphql_request::<GetJRangeBwData>()
+        .times(1)
+        .returning(|_, _, _| {
+            Ok(GraphQLResponse {
+                data: None,
+                errors: Some(vec![wxrust::models::GraphQLError { message: "Too many requests".to_string() }]),
+            })
+        });
+
+    let oldest = chrono::NaiveDate::from_ymd_opt(2025, 10, 1).unwrap();
+    let latest = chrono::NaiveDate::from_ymd_opt(2025, 10, 31).unwrap();
+    let result = get_bodyweights(&mock_client, &token, oldest, latest).await;
+    assert_eq!(result.unwrap_err(), "Too many requests");
+}
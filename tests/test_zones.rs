@@ -0,0 +1,53 @@
+mod common;
+
+use wxrust::models::Set;
+use wxrust::zones::{best_e1rm, check, render_zones, session_zones, zone_index};
+use common::{day, set_x};
+
+#[test]
+fn test_zone_index() {
+    assert_eq!(zone_index(40.0), 0);
+    assert_eq!(zone_index(55.0), 1);
+    assert_eq!(zone_index(69.9), 1);
+    assert_eq!(zone_index(85.0), 3);
+    assert_eq!(zone_index(102.0), 4);
+}
+
+#[test]
+fn test_session_zones() {
+    let days = vec![
+        // the 40 x 10 warm-up is not a working set
+        ("2025-10-01".to_string(), day("squat", vec![set_x(40.0, 10, 1), set_x(75.0, 5, 5), set_x(85.0, 3, 3)])),
+        ("2025-10-03".to_string(), day("squat", vec![set_x(92.0, 3, 1)])),
+    ];
+    let sessions = session_zones(&days, "squat", |s| s.w.map(|w| w / 100.0 * 100.0));
+    assert_eq!(sessions.len(), 2);
+    assert_eq!(sessions[0].reps, [0, 0, 25, 9, 0]);
+    assert_eq!(sessions[0].sets, [0, 0, 5, 3, 0]);
+    assert_eq!(check(&sessions[0]), vec!["70-79% 25 > 24", "80-89% 9 < 10"]);
+    assert_eq!(check(&sessions[1]), vec!["90%+ 3 < 4", "90%+ 1 set outside 1-2 reps"]);
+
+    let server = session_zones(&days, "squat", |s| s.intensity_pct());
+    assert!(server.is_empty());
+}
+
+#[test]
+fn test_best_e1rm() {
+    let days = vec![
+        ("2025-10-01".to_string(), day("squat", vec![set_x(100.0, 1, 1)])),
+        ("2025-10-03".to_string(), day("squat", vec![Set { est1rm: Some(120.0), ..set_x(100.0, 5, 1) }])),
+    ];
+    assert_eq!(best_e1rm(&days, "squat"), Some((120.0, "2025-10-03".to_string())));
+    assert_eq!(best_e1rm(&days, "bench"), None);
+}
+
+#[test]
+fn test_render_zones() {
+    common::no_color();
+    let days = vec![("2025-10-01".to_string(), day("squat", vec![set_x(75.0, 5, 4)]))];
+    let sessions = session_zones(&days, "squat", |s| s.w);
+    let out = render_zones(&sessions, "training max 100");
+    assert!(out.starts_with("reference  training max 100\n"));
+    assert!(out.contains("70-79%     4    20   100%  3-6 reps/set, 18 (12-24) per session"));
+    assert!(out.contains("2025-10-01                    20                ✓"));
+}